@@ -0,0 +1,40 @@
+// Pulls the addresses out of pasted text or an imported file, be it one address per line,
+// CSV or "Name <address>" lists. Addresses are lowercased and only returned once.
+pub fn parse(text: &str) -> Vec<String> {
+    let mut addresses: Vec<String> = Vec::new();
+    for token in text.split(|c: char| c.is_whitespace() || matches!(c, ',' | ';')) {
+        let token = token
+            .trim_matches(|c: char| matches!(c, '<' | '>' | '"' | '\'' | '(' | ')'))
+            .trim_start_matches("mailto:")
+            .to_lowercase();
+        if token.contains('@') && !addresses.contains(&token) {
+            addresses.push(token);
+        }
+    }
+    addresses
+}
+
+// Like parse(), but keeps what else is on an address's line as its display name,
+// e.g. "Jane Doe <jane@example.org>" or "jane@example.org,Jane Doe"
+pub fn parse_named(text: &str) -> Vec<(String, String)> {
+    let mut named: Vec<(String, String)> = Vec::new();
+    for line in text.lines() {
+        let addresses = parse(line);
+        // Without exactly one address on the line there's no telling whose name the rest is
+        let display_name = if addresses.len() == 1 {
+            line.split([',', ';', '\t', '<', '>'])
+                .map(|field| field.trim().trim_matches('"').trim())
+                .filter(|field| !field.is_empty() && !field.contains('@'))
+                .collect::<Vec<&str>>()
+                .join(" ")
+        } else {
+            String::new()
+        };
+        for address in addresses {
+            if !named.iter().any(|(a, _)| *a == address) {
+                named.push((address, display_name.clone()));
+            }
+        }
+    }
+    named
+}
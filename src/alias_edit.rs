@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+
+use ratatui::{prelude::*, widgets::*};
+use reqwest::{Method, Url};
+use serde_json::{Map, value::Value};
+use tui_textarea::{TextArea, Input, Key};
+
+use crate::{config::Config, popup::{self, Popup, PopupReqParam, PopupStatus}, response::{Response, ResponseType}};
+
+#[derive(Clone)]
+pub struct AliasEdit<'a> {
+    config: Config,
+    text_area: TextArea<'a>,
+    loaded: bool,
+}
+
+impl<'a> AliasEdit<'a> {
+    pub fn new(config: Config) -> Self {
+        let mut text_area = TextArea::default();
+        text_area.set_block(Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(" Acceptable Aliases (loading...) ".to_string())
+            .style(Style::default().fg(Color::Blue)),
+        );
+
+        Self {
+            config,
+            text_area,
+            loaded: false,
+        }
+    }
+
+    fn aliases(&self) -> Vec<String> {
+        self.text_area.lines()
+            .iter()
+            .map(|line| line.trim().to_string())
+            .filter(|line| !line.is_empty())
+            .collect()
+    }
+}
+
+impl Popup for AliasEdit<'_> {
+    fn render(&mut self, frame: &mut Frame) {
+        let area = popup::area(frame, 80, 12);
+
+        frame.render_widget(&self.text_area, area);
+    }
+
+    fn input(&mut self, input: Input) -> PopupStatus {
+        let mut status = PopupStatus::Continue;
+        match input {
+            Input { key: Key::Esc, .. } => status = PopupStatus::Cancel,
+            // Nothing is edited before the aliases are known, they'd replace it when they come in
+            _input if !self.loaded => {}
+            Input { key: Key::Char('s'), ctrl: true, .. } => status = PopupStatus::Submit,
+            input => { self.text_area.input(input); }
+        }
+
+        status
+    }
+
+    // One alias per line, so line breaks are kept
+    fn paste(&mut self, text: &str) {
+        if self.loaded {
+            self.text_area.insert_str(text);
+        }
+    }
+
+    fn submit(&self) -> Option<PopupReqParam> {
+        let url = Url::parse(&format!("{}://{}:{}/3.1/lists/{}/config",
+            self.config.protocol(),
+            self.config.host(),
+            self.config.port(),
+            self.config.list().unwrap().list_id())).unwrap();
+
+        let mut map = Map::new();
+        map.insert("acceptable_aliases".to_string(), Value::from(self.aliases()));
+
+        Some(PopupReqParam::new_json(Method::PATCH, url, map))
+    }
+
+    fn load(&self) -> Vec<PopupReqParam> {
+        let url = Url::parse(&format!("{}://{}:{}/3.1/lists/{}/config/acceptable_aliases",
+            self.config.protocol(),
+            self.config.host(),
+            self.config.port(),
+            self.config.list().unwrap().list_id())).unwrap();
+
+        vec![PopupReqParam::new_for(Method::GET, url, HashMap::new(), ResponseType::PopupData("acceptable_aliases".to_string()))]
+    }
+
+    fn loaded(&mut self, response: Response) {
+        let mut title = " Acceptable Aliases (one per line, Ctrl-S saves, Esc cancels) ".to_string();
+        if response.is_success() {
+            let aliases = acceptable_aliases(&response.text());
+            self.text_area = TextArea::new(aliases);
+            self.loaded = true;
+        } else {
+            title = format!(" Can't load acceptable aliases: {} ", response.status());
+        }
+        self.text_area.set_block(Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(title)
+            .style(Style::default().fg(Color::Blue)),
+        );
+    }
+}
+
+// Mailman answers with {"acceptable_aliases": [...], "http_etag": ...}
+pub fn acceptable_aliases(text: &str) -> Vec<String> {
+    serde_json::from_str::<Value>(text)
+        .ok()
+        .and_then(|value| value.get("acceptable_aliases").cloned())
+        .and_then(|aliases| aliases.as_array().cloned())
+        .map_or(Vec::new(), |aliases| aliases.iter().filter_map(|a| a.as_str().map(str::to_string)).collect())
+}
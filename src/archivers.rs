@@ -0,0 +1,52 @@
+use serde_json::{Map, value::Value};
+
+#[derive(Debug, Clone)]
+pub struct Archivers {
+    entries: Vec<Entry>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Entry {
+    name: String,
+    enabled: bool,
+}
+
+impl Archivers {
+    // Mailman answers with a plain object like {"hyperkitty": true, "mail-archive": false, "http_etag": ...}
+    pub fn new(text: &str) -> Result<Self, serde_json::Error> {
+        let map: Map<String, Value> = serde_json::from_str(text)?;
+        let mut entries: Vec<Entry> = map.iter().filter_map(|(name, enabled)| {
+            Some(Entry {
+                name: name.clone(),
+                enabled: enabled.as_bool()?,
+            })
+        }).collect();
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(Self {
+            entries,
+        })
+    }
+
+    pub fn table(&self) -> (Vec<String>, Vec<Vec<String>>) {
+        let header = vec!["archiver".to_string(), "enabled".to_string()];
+        let rows = self.entries.iter().map(|entry| {
+            vec![entry.name.clone(), if entry.enabled { "[x]" } else { "[ ]" }.to_string()]
+        }).collect();
+        (header, rows)
+    }
+
+    pub fn entries(&self) -> Option<Vec<Entry>> {
+        Some(self.entries.clone())
+    }
+}
+
+impl Entry {
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+}
@@ -0,0 +1,135 @@
+use std::{fs::OpenOptions, io::Write, path::PathBuf, time::{SystemTime, UNIX_EPOCH}};
+
+use directories::ProjectDirs;
+use reqwest::{Method, Url};
+use serde::{Serialize, Deserialize};
+use serde_json::{Map, value::Value};
+
+use crate::{config::Config, timestamp};
+
+// One line of the audit log, written for every request changing something on the server
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Entry {
+    timestamp: String,
+    user: String,
+    server: String,
+    method: String,
+    path: String,
+    body: Value,
+    status: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct Audit {
+    entries: Vec<Entry>,
+}
+
+// In marge's data directory, e.g. ~/.local/share/marge on Linux
+pub fn path() -> Option<PathBuf> {
+    ProjectDirs::from("org", "keienb", "marge").map(|project_dirs| project_dirs.data_dir().join("audit.jsonl"))
+}
+
+fn is_secret(key: &str) -> bool {
+    let key = key.to_lowercase();
+    ["password", "secret", "token"].iter().any(|secret| key.contains(secret))
+}
+
+// Long enough for any setting, short of whole messages like those injected into a queue
+const MAX_VALUE: usize = 200;
+
+fn redact(map: &Map<String, Value>) -> Value {
+    Value::Object(map.iter().map(|(key, value)| {
+        match value {
+            _ if is_secret(key) => (key.clone(), Value::String("***".to_string())),
+            Value::String(text) if text.chars().count() > MAX_VALUE => {
+                let start: String = text.chars().take(MAX_VALUE).collect();
+                (key.clone(), Value::String(format!("{}... ({} bytes)", start, text.len())))
+            }
+            _ => (key.clone(), value.clone()),
+        }
+    }).collect())
+}
+
+// GETs and the POSTs Mailman uses for searching, e.g. members/find
+fn is_read_only(method: &Method, url: &Url) -> bool {
+    *method == Method::GET || (*method == Method::POST && url.path().ends_with("/find"))
+}
+
+// Appends to the log; reading requests aren't logged. A log that can't be written
+// mustn't keep admins from working, so request() goes on regardless.
+pub fn record(config: &Config, method: &Method, url: &Url, body: &Map<String, Value>, status: String) -> std::io::Result<()> {
+    if is_read_only(method, url) {
+        return Ok(());
+    }
+    let Some(path) = path() else {
+        return Ok(());
+    };
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    let entry = Entry {
+        timestamp: timestamp::format_utc(now),
+        user: std::env::var("USER").or_else(|_| std::env::var("USERNAME")).unwrap_or("(unknown)".to_string()),
+        server: format!("{}@{}://{}:{}", config.username(), config.protocol(), config.host(), config.port()),
+        method: method.to_string(),
+        path: url.path().to_string(),
+        body: redact(body),
+        status,
+    };
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let line = serde_json::to_string(&entry)?;
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(format!("{}\n", line).as_bytes())
+}
+
+impl Audit {
+    // Newest first; lines that aren't entries, e.g. from a crash while writing, are skipped
+    pub fn read() -> Result<Self, String> {
+        let path = path().ok_or("Can't find a directory for the audit log".to_string())?;
+        let text = match std::fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(format!("Can't read {}: {}", path.display(), e)),
+        };
+        let mut entries: Vec<Entry> = text.lines().filter_map(|line| serde_json::from_str(line).ok()).collect();
+        entries.reverse();
+        Ok(Self {
+            entries,
+        })
+    }
+
+    pub fn table(&self) -> (Vec<String>, Vec<Vec<String>>) {
+        let header = ["timestamp", "user", "method", "path", "status"].iter().map(|c| c.to_string()).collect();
+        let rows = self.entries.iter().map(|entry| vec![
+            entry.timestamp.clone(),
+            entry.user.clone(),
+            entry.method.clone(),
+            entry.path.clone(),
+            entry.status.clone(),
+        ]).collect();
+        (header, rows)
+    }
+
+    pub fn entries(&self) -> Option<Vec<Entry>> {
+        if self.entries.is_empty() {
+            None
+        } else {
+            Some(self.entries.clone())
+        }
+    }
+}
+
+impl Entry {
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = vec![
+            format!("{:<10} {}", "timestamp", self.timestamp),
+            format!("{:<10} {}", "user", self.user),
+            format!("{:<10} {}", "server", self.server),
+            format!("{:<10} {} {}", "request", self.method, self.path),
+            format!("{:<10} {}", "status", self.status),
+            String::new(),
+        ];
+        lines.extend(serde_json::to_string_pretty(&self.body).unwrap_or_default().lines().map(str::to_string));
+        lines
+    }
+}
@@ -0,0 +1,48 @@
+use ratatui::{prelude::*, widgets::*};
+use tui_textarea::{Input, Key};
+
+use crate::{audit, popup::{self, Popup, PopupStatus}};
+
+#[derive(Clone)]
+pub struct AuditDetail {
+    entry: audit::Entry,
+    scroll: u16,
+}
+
+impl AuditDetail {
+    pub fn new(entry: audit::Entry) -> Self {
+        Self {
+            entry,
+            scroll: 0,
+        }
+    }
+}
+
+impl Popup for AuditDetail {
+    fn render(&mut self, frame: &mut Frame) {
+        let area = popup::area(frame, 80, 18);
+
+        let text: Vec<Line> = self.entry.lines().into_iter().map(Line::raw).collect();
+        let paragraph = Paragraph::new(text)
+            .scroll((self.scroll, 0))
+            .block(Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(" Audit Log Entry (Up/Down: scroll, Esc: close) ".to_string())
+            .style(Style::default().fg(Color::Blue)),
+        );
+        frame.render_widget(paragraph, area);
+    }
+
+    fn input(&mut self, input: Input) -> PopupStatus {
+        match input {
+            Input { key: Key::Esc, .. } |
+            Input { key: Key::Enter, .. } => return PopupStatus::Cancel,
+            Input { key: Key::Down, .. } => self.scroll = self.scroll.saturating_add(1),
+            Input { key: Key::Up, .. } => self.scroll = self.scroll.saturating_sub(1),
+            _input => {}
+        }
+        PopupStatus::Continue
+    }
+
+}
@@ -0,0 +1,247 @@
+use std::{collections::{BTreeMap, HashMap}, path::Path, time::{SystemTime, UNIX_EPOCH}};
+
+use color_eyre::eyre::{eyre, Result};
+use reqwest::{Client, Method, Url};
+use serde::{Serialize, Deserialize};
+use serde_json::{Map, value::Value};
+
+use crate::{config::Config, config_clone, popup::PopupReqParam, request::{self, get_json}, templates, timestamp};
+
+const ROLES: [&str; 4] = ["owner", "moderator", "member", "nonmember"];
+
+// Bumped whenever a bundle written by this version can't be restored by older ones
+const FORMAT: u32 = 1;
+
+// Everything needed to recreate a list, on the same server or another one
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Bundle {
+    format: u32,
+    created_at: String,
+    server: String,
+    fqdn_listname: String,
+    config: Map<String, Value>,
+    rosters: BTreeMap<String, Vec<Membership>>,
+    bans: Vec<String>,
+    header_matches: Vec<HeaderMatch>,
+    templates: BTreeMap<String, String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Membership {
+    email: String,
+    display_name: Option<String>,
+    delivery_mode: Option<String>,
+    moderation_action: Option<String>,
+    preferences: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HeaderMatch {
+    header: String,
+    pattern: String,
+    action: Option<String>,
+    tag: Option<String>,
+}
+
+fn entries(value: &Value) -> Vec<Value> {
+    value.get("entries").and_then(Value::as_array).cloned().unwrap_or_default()
+}
+
+fn string(value: &Value, key: &str) -> Option<String> {
+    value.get(key).and_then(Value::as_str).map(str::to_string)
+}
+
+// Mailman adds these to every resource; they mean nothing on another server
+pub fn strip(mut map: Map<String, Value>) -> Map<String, Value> {
+    map.remove("http_etag");
+    map.remove("self_link");
+    map
+}
+
+impl Bundle {
+    pub async fn new(client: &mut Client, config: &Config, list: &str) -> Result<Self> {
+        let list: Value = get_json(client, config, &format!("lists/{}", list)).await?;
+        let fqdn_listname = string(&list, "fqdn_listname").ok_or(eyre!("The list has no fqdn_listname"))?;
+        let list_id = string(&list, "list_id").ok_or(eyre!("The list has no list_id"))?;
+        let base = format!("lists/{}", list_id);
+
+        let mut list_config = strip(get_json(client, config, &format!("{}/config", base)).await?);
+        let aliases: Value = get_json(client, config, &format!("{}/config/acceptable_aliases", base)).await?;
+        list_config.insert("acceptable_aliases".to_string(), aliases.get("acceptable_aliases").cloned().unwrap_or(Value::Array(Vec::new())));
+
+        let mut rosters = BTreeMap::new();
+        for role in ROLES {
+            let roster: Value = get_json(client, config, &format!("{}/roster/{}", base, role)).await?;
+            let mut memberships = Vec::new();
+            for entry in entries(&roster) {
+                let preferences = match string(&entry, "member_id") {
+                    Some(member_id) => strip(get_json(client, config, &format!("members/{}/preferences", member_id)).await?),
+                    None => Map::new(),
+                };
+                memberships.push(Membership {
+                    email: string(&entry, "email").unwrap_or_default(),
+                    display_name: string(&entry, "display_name"),
+                    delivery_mode: string(&entry, "delivery_mode"),
+                    moderation_action: string(&entry, "moderation_action"),
+                    preferences,
+                });
+            }
+            rosters.insert(role.to_string(), memberships);
+        }
+
+        let bans: Value = get_json(client, config, &format!("{}/bans", base)).await?;
+        let header_matches: Value = get_json(client, config, &format!("{}/header-matches", base)).await?;
+        let uris: templates::Uris = get_json(client, config, &format!("{}/uris", base)).await?;
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+        Ok(Self {
+            format: FORMAT,
+            created_at: timestamp::format_utc(now),
+            server: config.host().clone(),
+            fqdn_listname,
+            config: list_config,
+            rosters,
+            bans: entries(&bans).iter().filter_map(|ban| string(ban, "email")).collect(),
+            header_matches: entries(&header_matches).iter().map(|header_match| HeaderMatch {
+                header: string(header_match, "header").unwrap_or_default(),
+                pattern: string(header_match, "pattern").unwrap_or_default(),
+                action: string(header_match, "action"),
+                tag: string(header_match, "tag"),
+            }).collect(),
+            templates: uris.uris(),
+        })
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path).map_err(|e| eyre!("Can't read {}: {}", path.display(), e))?;
+        let bundle: Self = serde_json::from_str(&text).map_err(|e| eyre!("{} is no list backup: {}", path.display(), e))?;
+        if bundle.format > FORMAT {
+            return Err(eyre!("{} was written by a newer marge (format {})", path.display(), bundle.format));
+        }
+        Ok(bundle)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?).map_err(|e| eyre!("Can't write {}: {}", path.display(), e))?;
+        Ok(())
+    }
+
+    pub fn fqdn_listname(&self) -> String {
+        self.fqdn_listname.clone()
+    }
+
+    pub fn summary(&self) -> Vec<String> {
+        let mut lines = vec![
+            format!("{} from {}, saved {}", self.fqdn_listname, self.server, self.created_at),
+            format!("{} settings", self.config.len()),
+        ];
+        lines.extend(self.rosters.iter().map(|(role, memberships)| format!("{} {}s", memberships.len(), role)));
+        lines.push(format!("{} bans, {} header filters, {} templates", self.bans.len(), self.header_matches.len(), self.templates.len()));
+        lines
+    }
+
+    // Recreates the list as fqdn_listname. Creating it fails if it exists already;
+    // everything else is applied to the existing list then.
+    pub fn requests(&self, config: &Config, fqdn_listname: &str) -> Vec<PopupReqParam> {
+        let url = |path: &str| Url::parse(&format!("{}://{}:{}/3.1/{}",
+            config.protocol(),
+            config.host(),
+            config.port(),
+            path)).unwrap();
+        let base = format!("lists/{}", fqdn_listname);
+        let list_id = fqdn_listname.replacen('@', ".", 1);
+        let mut params = Vec::new();
+
+        let mut map = HashMap::new();
+        map.insert("fqdn_listname".to_string(), fqdn_listname.to_string());
+        params.push(PopupReqParam::new(Method::POST, url("lists"), map).with_label(format!("creating {}", fqdn_listname)));
+
+        let settings = self.config.iter()
+            .filter(|(key, value)| !config_clone::READ_ONLY.contains(&key.as_str()) && !value.is_null())
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        params.push(PopupReqParam::new_json(Method::PATCH, url(&format!("{}/config", base)), settings).with_label("restoring settings".to_string()));
+
+        for (role, memberships) in &self.rosters {
+            for membership in memberships {
+                let mut map = HashMap::new();
+                map.insert("list_id".to_string(), list_id.clone());
+                map.insert("subscriber".to_string(), membership.email.clone());
+                map.insert("role".to_string(), role.clone());
+                map.insert("display_name".to_string(), membership.display_name.clone().unwrap_or_default());
+                if let Some(delivery_mode) = &membership.delivery_mode {
+                    map.insert("delivery_mode".to_string(), delivery_mode.clone());
+                }
+                map.insert("pre_verified".to_string(), "true".to_string());
+                map.insert("pre_confirmed".to_string(), "true".to_string());
+                map.insert("pre_approved".to_string(), "true".to_string());
+                map.insert("send_welcome_message".to_string(), "false".to_string());
+                params.push(PopupReqParam::new(Method::POST, url("members"), map).with_label(format!("subscribing {} as {}", membership.email, role)));
+
+                // The new membership's id isn't known yet, so it's addressed by role and email
+                let member = format!("{}/{}/{}", base, role, membership.email);
+                if let Some(moderation_action) = &membership.moderation_action {
+                    let mut map = HashMap::new();
+                    map.insert("moderation_action".to_string(), moderation_action.clone());
+                    params.push(PopupReqParam::new(Method::PATCH, url(&member), map)
+                        .with_label(format!("moderation of {}", membership.email))
+                        .if_previous_succeeded());
+                }
+                if !membership.preferences.is_empty() {
+                    params.push(PopupReqParam::new_json(Method::PATCH, url(&format!("{}/preferences", member)), membership.preferences.clone())
+                        .with_label(format!("preferences of {}", membership.email))
+                        .if_previous_succeeded());
+                }
+            }
+        }
+
+        for email in &self.bans {
+            let mut map = HashMap::new();
+            map.insert("email".to_string(), email.clone());
+            params.push(PopupReqParam::new(Method::POST, url(&format!("{}/bans", base)), map).with_label(format!("banning {}", email)));
+        }
+
+        for header_match in &self.header_matches {
+            let mut map = HashMap::new();
+            map.insert("header".to_string(), header_match.header.clone());
+            map.insert("pattern".to_string(), header_match.pattern.clone());
+            if let Some(action) = &header_match.action {
+                map.insert("action".to_string(), action.clone());
+            }
+            if let Some(tag) = &header_match.tag {
+                map.insert("tag".to_string(), tag.clone());
+            }
+            params.push(PopupReqParam::new(Method::POST, url(&format!("{}/header-matches", base)), map)
+                .with_label(format!("header filter {}: {}", header_match.header, header_match.pattern)));
+        }
+
+        if !self.templates.is_empty() {
+            let map = self.templates.clone().into_iter().collect();
+            params.push(PopupReqParam::new(Method::PATCH, url(&format!("{}/uris", base)), map).with_label("restoring templates".to_string()));
+        }
+
+        params
+    }
+}
+
+// `marge backup`
+pub async fn run_backup(client: &mut Client, config: &Config, list: &str, file: &Path) -> Result<()> {
+    let bundle = Bundle::new(client, config, list).await?;
+    bundle.save(file)?;
+    for line in bundle.summary() {
+        println!("{}", line);
+    }
+    println!("Saved to {}", file.display());
+    Ok(())
+}
+
+// `marge restore`
+pub async fn run_restore(client: &mut Client, config: &Config, file: &Path, list: Option<&String>) -> Result<()> {
+    let bundle = Bundle::load(file)?;
+    for line in bundle.summary() {
+        println!("{}", line);
+    }
+    let fqdn_listname = list.cloned().unwrap_or(bundle.fqdn_listname());
+    println!("Restoring as {} on {}", fqdn_listname, config.host());
+    request::send_all(client, config, bundle.requests(config, &fqdn_listname)).await
+}
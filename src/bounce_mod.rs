@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+
+use ratatui::{prelude::*, widgets::*};
+use reqwest::{Method, Url};
+use serde_json::value::Value;
+use tui_textarea::{Input, Key};
+
+use crate::{bounces, config::Config, popup::{self, Buttons, Popup, PopupReqParam, PopupStatus}, response::{Response, ResponseType}};
+
+#[derive(Clone)]
+pub struct BounceMod {
+    config: Config,
+    delivery_status: String,
+    buttons: Buttons,
+}
+
+impl BounceMod {
+    pub fn new(config: Config) -> Self {
+        Self {
+            config,
+            delivery_status: "...".to_string(),
+            buttons: Buttons::new(&[("Re-enable", Key::Enter), ("Close", Key::Esc)]),
+        }
+    }
+
+    fn url(&self, path: &str) -> Url {
+        Url::parse(&format!("{}://{}:{}/3.1/members/{}{}",
+            self.config.protocol(),
+            self.config.host(),
+            self.config.port(),
+            self.config.member().unwrap().member_id(),
+            path)).unwrap()
+    }
+
+}
+
+impl Popup for BounceMod {
+    fn render(&mut self, frame: &mut Frame) {
+        let area = popup::area(frame, 80, 11);
+
+        let member = self.config.member().unwrap();
+        let text = vec![
+            Line::from(format!("{:<18} {}", "email", member.email())),
+            Line::from(format!("{:<18} {}", "bounce_score", member.bounce_score())),
+            Line::from(format!("{:<18} {}", "warnings_sent", member.total_warnings_sent())),
+            Line::from(format!("{:<18} {}", "last_warning_sent", member.last_warning())),
+            Line::from(format!("{:<18} {}", "delivery_status", self.delivery_status)),
+            Line::from(""),
+            // A member PATCH only takes address, delivery_mode and moderation_action
+            Line::from("Mailman's REST API can't reset the bounce score, it goes stale on its own."),
+            Line::from("Type 'e' or Enter to re-enable delivery, Esc to close"),
+        ];
+        let paragraph = Paragraph::new(text)
+            .block(Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(" Bounces ".to_string())
+            .style(Style::default().fg(Color::Blue)),
+        );
+
+        frame.render_widget(paragraph, area);
+        self.buttons.render(frame, area);
+    }
+
+    fn input(&mut self, input: Input) -> PopupStatus {
+        let mut status = PopupStatus::Continue;
+        match input {
+            Input { key: Key::Esc, .. } => status = PopupStatus::Cancel,
+            Input { key: Key::Char('e'), .. } |
+            Input { key: Key::Enter, .. } => status = PopupStatus::Submit,
+            _input => {}
+        }
+
+        status
+    }
+
+    fn buttons(&self) -> Option<&Buttons> {
+        Some(&self.buttons)
+    }
+
+    fn submit(&self) -> Option<PopupReqParam> {
+        let mut map = HashMap::new();
+        map.insert("delivery_status".to_string(), "enabled".to_string());
+        Some(PopupReqParam::new(Method::PATCH, self.url("/preferences"), map))
+    }
+
+    fn load(&self) -> Vec<PopupReqParam> {
+        vec![PopupReqParam::new_for(Method::GET, self.url("/preferences"), HashMap::new(), ResponseType::PopupData("preferences".to_string()))]
+    }
+
+    fn loaded(&mut self, response: Response) {
+        if !response.is_success() {
+            self.delivery_status = format!("(can't load: {})", response.status());
+            return;
+        }
+        self.delivery_status = delivery_status(&response.text())
+            .map_or("(unknown)".to_string(), |status| bounces::describe(&status));
+    }
+}
+
+// Members without an explicit preference inherit "enabled" and Mailman leaves the key out
+pub fn delivery_status(text: &str) -> Option<String> {
+    let value: Value = serde_json::from_str(text).ok()?;
+    Some(value.get("delivery_status").and_then(Value::as_str).unwrap_or("enabled").to_string())
+}
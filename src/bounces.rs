@@ -0,0 +1,71 @@
+use std::collections::HashMap;
+
+use crate::members::{Entry, Members};
+
+// The members of a list, most bouncing first, together with their delivery status
+#[derive(Debug, Clone)]
+pub struct Bounces {
+    entries: Vec<Entry>,
+    delivery_status: HashMap<String, String>,
+}
+
+impl Bounces {
+    pub fn new(text: &str) -> Result<Self, serde_json::Error> {
+        let members: Members = serde_json::from_str(text)?;
+        let mut entries = members.entries().unwrap_or_default();
+        entries.sort_by(|a, b| b.bounce_score().cmp(&a.bounce_score())
+            .then(b.total_warnings_sent().cmp(&a.total_warnings_sent())));
+        Ok(Self {
+            entries,
+            delivery_status: HashMap::new(),
+        })
+    }
+
+    pub fn table(&self) -> (Vec<String>, Vec<Vec<String>>) {
+        let header = ["email", "bounce_score", "warnings_sent", "last_warning_sent", "delivery_status"]
+            .iter().map(|c| c.to_string()).collect();
+        let rows = self.entries.iter().map(|entry| vec![
+            entry.email(),
+            entry.bounce_score().to_string(),
+            entry.total_warnings_sent().to_string(),
+            entry.last_warning(),
+            self.delivery_status(&entry.member_id()),
+        ]).collect();
+        (header, rows)
+    }
+
+    pub fn entries(&self) -> Option<Vec<Entry>> {
+        if self.entries.is_empty() {
+            None
+        } else {
+            Some(self.entries.clone())
+        }
+    }
+
+    // Asking for the preferences of every member would flood the server on big lists,
+    // and only members with a bounce history can have been disabled by bounces
+    pub fn with_history(&self) -> Vec<String> {
+        self.entries.iter()
+            .filter(|entry| entry.bounce_score() > 0 || entry.total_warnings_sent() > 0)
+            .map(|entry| entry.member_id())
+            .collect()
+    }
+
+    pub fn set_delivery_status(&mut self, member_id: &str, delivery_status: String) {
+        self.delivery_status.insert(member_id.to_string(), delivery_status);
+    }
+
+    pub fn delivery_status(&self, member_id: &str) -> String {
+        self.delivery_status.get(member_id).map_or("-".to_string(), |status| describe(status))
+    }
+}
+
+pub fn describe(delivery_status: &str) -> String {
+    match delivery_status {
+        "enabled" => "enabled".to_string(),
+        "by_bounces" => "disabled by bounces".to_string(),
+        "by_user" => "disabled by member".to_string(),
+        "by_moderator" => "disabled by moderator".to_string(),
+        status => format!("disabled ({})", status),
+    }
+}
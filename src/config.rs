@@ -8,6 +8,9 @@ use crate::domains;
 use crate::lists;
 use crate::members;
 use crate::messages;
+use crate::templates;
+use crate::header_matches;
+use crate::queue;
 
 #[derive(Clone, Deserialize, Serialize)]
 pub struct Config {
@@ -20,6 +23,23 @@ pub struct Config {
     list: Option<lists::Entry>,
     member: Option<members::Entry>,
     message: Option<messages::Entry>,
+    template: Option<templates::Entry>,
+    header_match: Option<header_matches::Entry>,
+    queue_file: Option<queue::Entry>,
+    #[serde(default = "default_list_columns")]
+    list_columns: Vec<String>,
+    #[serde(default = "default_member_columns")]
+    member_columns: Vec<String>,
+}
+
+fn default_list_columns() -> Vec<String> {
+    ["fqdn_listname", "display_name", "member_count", "volume", "advertised"]
+        .iter().map(|c| c.to_string()).collect()
+}
+
+fn default_member_columns() -> Vec<String> {
+    ["email", "display_name", "role", "delivery_mode", "bounce_score"]
+        .iter().map(|c| c.to_string()).collect()
 }
 
 impl Config {
@@ -33,6 +53,11 @@ impl Config {
         let list = None;
         let member = None;
         let message = None;
+        let template = None;
+        let header_match = None;
+        let queue_file = None;
+        let list_columns = default_list_columns();
+        let member_columns = default_member_columns();
 
         Config {
             username,
@@ -44,6 +69,11 @@ impl Config {
             list,
             member,
             message,
+            template,
+            header_match,
+            queue_file,
+            list_columns,
+            member_columns,
         }
     }
 
@@ -112,9 +142,9 @@ impl Config {
         self.domain = domain;
     }
 
-    // pub fn domain(&self) -> Option<domains::Entry> {
-    //     self.domain.clone()
-    // }
+    pub fn domain(&self) -> Option<domains::Entry> {
+        self.domain.clone()
+    }
 
     pub fn set_list(&mut self, list: Option<lists::Entry>) {
         self.list = list;
@@ -140,8 +170,40 @@ impl Config {
         self.message.clone()
     }
 
+    pub fn set_template(&mut self, template: Option<templates::Entry>) {
+        self.template = template;
+    }
+
+    pub fn template(&self) -> Option<templates::Entry> {
+        self.template.clone()
+    }
+
+    pub fn set_header_match(&mut self, header_match: Option<header_matches::Entry>) {
+        self.header_match = header_match;
+    }
+
+    pub fn header_match(&self) -> Option<header_matches::Entry> {
+        self.header_match.clone()
+    }
+
+    pub fn set_queue_file(&mut self, queue_file: Option<queue::Entry>) {
+        self.queue_file = queue_file;
+    }
+
+    pub fn queue_file(&self) -> Option<queue::Entry> {
+        self.queue_file.clone()
+    }
+
+    pub fn list_columns(&self) -> &Vec<String> {
+        &self.list_columns
+    }
+
+    pub fn member_columns(&self) -> &Vec<String> {
+        &self.member_columns
+    }
+
     pub fn save(&self, config_dir: &PathBuf) {
-        let result = fs::create_dir_all(&config_dir);
+        let result = fs::create_dir_all(config_dir);
         match result {
             Ok(_) => {
                 let mut path = PathBuf::new();
@@ -158,16 +220,16 @@ impl Config {
                                 let result = serde_json::to_writer_pretty(file, &self);
                                 match result {
                                     Ok(_) => (),
-                                    Err(e) => eprintln!("Error while writing config file: {}", e.to_string())
+                                    Err(e) => eprintln!("Error while writing config file: {}", e)
                                 }
                         //    }
-                        //    Err(e) => eprintln!("Error while setting permissions for config file: {}", e.to_string())
+                        //    Err(e) => eprintln!("Error while setting permissions for config file: {}", e)
                         //}
                     }
-                    Err(e) => eprintln!("Error while creating config file: {}", e.to_string())
+                    Err(e) => eprintln!("Error while creating config file: {}", e)
                 }
             }
-            Err(e) => eprintln!("Error while creating config directory: {}", e.to_string())
+            Err(e) => eprintln!("Error while creating config directory: {}", e)
         }
     }
 }
\ No newline at end of file
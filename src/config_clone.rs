@@ -0,0 +1,276 @@
+use std::collections::HashMap;
+
+use ratatui::{prelude::*, widgets::*};
+use reqwest::{Method, Url};
+use serde_json::{Map, value::Value};
+use tui_textarea::{Input, Key};
+
+use crate::{config::Config, lists, popup::{self, Popup, PopupReqParam, PopupStatus}, response::{Response, ResponseType}, undo::Undo};
+
+// Mailman refuses to PATCH these, they're derived from the list's name or kept by Mailman itself
+pub const READ_ONLY: [&str; 19] = [
+    "bounces_address",
+    "created_at",
+    "digest_last_sent_at",
+    "fqdn_listname",
+    "http_etag",
+    "join_address",
+    "last_post_at",
+    "leave_address",
+    "list_id",
+    "list_name",
+    "mail_host",
+    "member_count",
+    "next_digest_number",
+    "no_reply_address",
+    "owner_address",
+    "post_id",
+    "posting_address",
+    "request_address",
+    "volume",
+];
+
+// What makes a list recognizable; shown in the diff, but not copied unless asked for
+const IDENTITY: [&str; 5] = [
+    "display_name",
+    "description",
+    "info",
+    "subject_prefix",
+    "acceptable_aliases",
+];
+
+#[derive(Clone)]
+struct Change {
+    key: String,
+    from: Value,
+    to: Value,
+    include: bool,
+}
+
+#[derive(Clone)]
+pub struct ConfigClone {
+    config: Config,
+    lists: Vec<lists::Entry>,
+    source: usize,
+    loading: bool,
+    source_config: Option<Map<String, Value>>,
+    target_config: Option<Map<String, Value>>,
+    changes: Option<Vec<Change>>,
+    selected: usize,
+    error: Option<String>,
+}
+
+impl ConfigClone {
+    pub fn new(config: Config, lists: Vec<lists::Entry>) -> Self {
+        let target = config.list().map(|list| list.list_id());
+        let lists = lists.into_iter().filter(|list| Some(list.list_id()) != target).collect();
+
+        Self {
+            config,
+            lists,
+            source: 0,
+            loading: false,
+            source_config: None,
+            target_config: None,
+            changes: None,
+            selected: 0,
+            error: None,
+        }
+    }
+
+    fn url(&self, list_id: &str) -> Url {
+        Url::parse(&format!("{}://{}:{}/3.1/lists/{}/config",
+            self.config.protocol(),
+            self.config.host(),
+            self.config.port(),
+            list_id)).unwrap()
+    }
+
+    // Once both configurations are there, the keys whose values differ make up the diff
+    fn diff(&mut self) {
+        let (Some(source), Some(target)) = (&self.source_config, &self.target_config) else {
+            return;
+        };
+        let changes = source.iter()
+            .filter(|(key, _)| !READ_ONLY.contains(&key.as_str()))
+            .filter(|(key, value)| target.get(*key) != Some(value))
+            .map(|(key, value)| Change {
+                key: key.clone(),
+                from: target.get(key).cloned().unwrap_or(Value::Null),
+                to: value.clone(),
+                include: !IDENTITY.contains(&key.as_str()),
+            })
+            .collect();
+        self.changes = Some(changes);
+        self.selected = 0;
+        self.loading = false;
+        self.error = None;
+    }
+
+    fn render_diff(&self, frame: &mut Frame, area: Rect, changes: &[Change]) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(format!(" Settings from {} ", self.lists[self.source].fqdn_listname()))
+            .style(Style::default().fg(Color::Blue));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let short = |value: &Value| {
+            let text = value.to_string();
+            if text.chars().count() > 24 {
+                format!("{}...", text.chars().take(21).collect::<String>())
+            } else {
+                text
+            }
+        };
+        let height = inner.height.saturating_sub(2) as usize;
+        let offset = self.selected.saturating_sub(height.saturating_sub(1));
+        let mut text: Vec<Line> = changes.iter().enumerate().skip(offset).take(height).map(|(i, change)| {
+            let line = format!("{} {}: {} -> {}",
+                if change.include { "[x]" } else { "[ ]" },
+                change.key,
+                short(&change.from),
+                short(&change.to));
+            if i == self.selected {
+                Line::styled(line, Style::default().fg(Color::Red).add_modifier(Modifier::BOLD))
+            } else {
+                Line::raw(line)
+            }
+        }).collect();
+        if changes.is_empty() {
+            text.push(Line::raw("Both lists already have the same settings"));
+        }
+        frame.render_widget(Paragraph::new(text), Rect { height: height as u16, ..inner });
+        let included = changes.iter().filter(|change| change.include).count();
+        let hint = format!("{} of {} differing keys: Space toggles, Enter applies, Esc goes back", included, changes.len());
+        frame.render_widget(Paragraph::new(hint).fg(Color::LightRed), Rect { y: (inner.y + inner.height).saturating_sub(1), height: 1, ..inner });
+    }
+}
+
+impl Popup for ConfigClone {
+    fn render(&mut self, frame: &mut Frame) {
+        let area = popup::area(frame, 80, 18);
+
+        if let Some(changes) = &self.changes {
+            self.render_diff(frame, area, changes);
+            return;
+        }
+
+        let mut text = vec![
+            Line::raw(format!("Clone the settings of {} from:", self.config.list().unwrap().fqdn_listname())),
+            Line::from(""),
+        ];
+        text.extend(self.lists.iter().enumerate().map(|(i, list)| {
+            if i == self.source {
+                Line::styled(format!("> {}", list.fqdn_listname()), Style::default().fg(Color::Red).add_modifier(Modifier::BOLD))
+            } else {
+                Line::raw(format!("  {}", list.fqdn_listname()))
+            }
+        }));
+        text.push(Line::from(""));
+        match &self.error {
+            Some(error) => text.push(Line::styled(error.clone(), Style::default().fg(Color::Red))),
+            None if self.loading => text.push(Line::raw("Loading both configurations...")),
+            None => text.push(Line::raw("Up/Down: source, Enter: show differences, Esc: cancel")),
+        }
+        let paragraph = Paragraph::new(text)
+            .block(Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(" Clone Settings ".to_string())
+            .style(Style::default().fg(Color::Blue)),
+        );
+        frame.render_widget(paragraph, area);
+    }
+
+    fn input(&mut self, input: Input) -> PopupStatus {
+        let mut status = PopupStatus::Continue;
+        if let Some(changes) = &mut self.changes {
+            match input {
+                Input { key: Key::Esc, .. } => {
+                    self.changes = None;
+                    self.source_config = None;
+                    self.target_config = None;
+                }
+                Input { key: Key::Enter, .. } if changes.iter().any(|change| change.include) => status = PopupStatus::Submit,
+                Input { key: Key::Char(' '), .. } => if let Some(change) = changes.get_mut(self.selected) {
+                    change.include = !change.include;
+                },
+                Input { key: Key::Down, .. } if self.selected + 1 < changes.len() => self.selected += 1,
+                Input { key: Key::Up, .. } => self.selected = self.selected.saturating_sub(1),
+                _input => {}
+            }
+            return status;
+        }
+        match input {
+            Input { key: Key::Esc, .. } => status = PopupStatus::Cancel,
+            Input { key: Key::Enter, .. } if !self.lists.is_empty() && !self.loading => {
+                self.loading = true;
+                self.error = None;
+                status = PopupStatus::Load;
+            }
+            Input { key: Key::Down, .. } if !self.lists.is_empty() => self.source = (self.source + 1) % self.lists.len(),
+            Input { key: Key::Up, .. } if !self.lists.is_empty() => self.source = (self.source + self.lists.len() - 1) % self.lists.len(),
+            _input => {}
+        }
+
+        status
+    }
+
+    fn submit(&self) -> Option<PopupReqParam> {
+        let map = self.changes.as_ref().map_or(Map::new(), |changes| {
+            changes.iter()
+                .filter(|change| change.include)
+                .map(|change| (change.key.clone(), change.to.clone()))
+                .collect()
+        });
+
+        Some(PopupReqParam::new_json(Method::PATCH, self.url(&self.config.list().unwrap().list_id()), map))
+    }
+
+    // Keys the target didn't have can't be PATCHed back to nothing, so they keep the copied value
+    fn undo(&self) -> Option<Undo> {
+        let changes = self.changes.as_ref()?;
+        let map: Map<String, Value> = changes.iter()
+            .filter(|change| change.include && !change.from.is_null())
+            .map(|change| (change.key.clone(), change.from.clone()))
+            .collect();
+        let list = self.config.list().unwrap();
+        let description = format!("cloning settings to {}", list.fqdn_listname());
+
+        Some(Undo::Restore(description, vec![PopupReqParam::new_json(Method::PATCH, self.url(&list.list_id()), map)]))
+    }
+
+    // Nothing to load until the source is chosen
+    fn load(&self) -> Vec<PopupReqParam> {
+        if !self.loading {
+            return Vec::new();
+        }
+        vec![
+            PopupReqParam::new_for(Method::GET, self.url(&self.lists[self.source].list_id()), HashMap::new(), ResponseType::PopupData("source".to_string())),
+            PopupReqParam::new_for(Method::GET, self.url(&self.config.list().unwrap().list_id()), HashMap::new(), ResponseType::PopupData("target".to_string())),
+        ]
+    }
+
+    fn loaded(&mut self, response: Response) {
+        let ResponseType::PopupData(data) = response.response_type() else {
+            return;
+        };
+        if !response.is_success() {
+            self.loading = false;
+            self.error = Some(format!("Can't load the {} configuration: {}", data, response.status()));
+            return;
+        }
+        match serde_json::from_str::<Map<String, Value>>(&response.text()) {
+            Ok(map) if data == "source" => self.source_config = Some(map),
+            Ok(map) => self.target_config = Some(map),
+            Err(e) => {
+                self.loading = false;
+                self.error = Some(format!("Can't read the {} configuration: {}", data, e));
+                return;
+            }
+        }
+        self.diff();
+    }
+}
@@ -0,0 +1,237 @@
+use std::collections::HashMap;
+
+use ratatui::{prelude::*, widgets::*};
+use reqwest::{Method, Url};
+use serde_json::value::Value;
+use tui_textarea::{TextArea, Input, Key};
+
+use crate::{config::Config, popup::{self, Popup, PopupReqParam, PopupStatus}, response::{Response, ResponseType}};
+
+const FREQUENCIES: [&str; 5] = ["yearly", "monthly", "quarterly", "weekly", "daily"];
+
+#[derive(Clone)]
+enum DigestAction {
+    Save,
+    Send,
+    Bump,
+}
+
+#[derive(Clone)]
+pub struct Digest<'a> {
+    config: Config,
+    volume: String,
+    next_digest_number: String,
+    last_sent_at: String,
+    digests_enabled: bool,
+    digest_send_periodic: bool,
+    digest_size_threshold: TextArea<'a>,
+    digest_volume_frequency: usize,
+    focus: usize,
+    action: DigestAction,
+    error: Option<String>,
+    loaded: bool,
+}
+
+impl<'a> Digest<'a> {
+    pub fn new(config: Config) -> Self {
+        let mut digest_size_threshold = TextArea::default();
+        digest_size_threshold.set_cursor_line_style(Style::default());
+
+        let mut digest = Self {
+            config,
+            volume: "...".to_string(),
+            next_digest_number: "...".to_string(),
+            last_sent_at: "...".to_string(),
+            digests_enabled: false,
+            digest_send_periodic: false,
+            digest_size_threshold,
+            digest_volume_frequency: 1,
+            focus: 0,
+            action: DigestAction::Save,
+            error: None,
+            loaded: false,
+        };
+        digest.set_focus(0);
+        digest
+    }
+
+    fn set_focus(&mut self, focus: usize) {
+        self.focus = focus;
+        if focus == 2 {
+            self.digest_size_threshold.set_cursor_style(Style::default().add_modifier(Modifier::REVERSED));
+        } else {
+            self.digest_size_threshold.set_cursor_style(Style::default());
+        }
+    }
+
+    fn threshold(&self) -> String {
+        self.digest_size_threshold.lines()[0].trim().to_string()
+    }
+
+    fn url(&self, path: &str) -> Url {
+        Url::parse(&format!("{}://{}:{}/3.1/lists/{}/{}",
+            self.config.protocol(),
+            self.config.host(),
+            self.config.port(),
+            self.config.list().unwrap().list_id(),
+            path)).unwrap()
+    }
+}
+
+impl Popup for Digest<'_> {
+    fn render(&mut self, frame: &mut Frame) {
+        let area = popup::area(frame, 80, 13);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(" Digest ".to_string())
+            .style(Style::default().fg(Color::Blue));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let check = |b: bool| if b { "[x]" } else { "[ ]" };
+        let rows = [
+            ("Volume", self.volume.clone()),
+            ("Next digest number", self.next_digest_number.clone()),
+            ("Last sent at", self.last_sent_at.clone()),
+            ("Digests enabled", check(self.digests_enabled).to_string()),
+            ("Send periodic", check(self.digest_send_periodic).to_string()),
+            ("Size threshold (KB)", String::new()),
+            ("Volume frequency", format!("< {} >", FREQUENCIES[self.digest_volume_frequency])),
+        ];
+        for (i, (label, value)) in rows.iter().enumerate() {
+            let label_area = Rect { x: inner.x, y: inner.y + i as u16, width: 22, height: 1 }.intersection(inner);
+            let value_area = Rect { x: inner.x + 22, y: inner.y + i as u16, width: inner.width.saturating_sub(22), height: 1 }.intersection(inner);
+            let mut label_style = Style::default().fg(Color::LightRed);
+            // The first three rows are read only
+            if i >= 3 && i - 3 == self.focus {
+                label_style = label_style.add_modifier(Modifier::BOLD | Modifier::REVERSED);
+            }
+            frame.render_widget(Paragraph::new(*label).style(label_style), label_area);
+            if i == 5 {
+                frame.render_widget(&self.digest_size_threshold, value_area);
+            } else {
+                frame.render_widget(Paragraph::new(value.clone()), value_area);
+            }
+        }
+
+        let hint = match &self.error {
+            Some(error) => vec![Line::styled(error.clone(), Style::default().fg(Color::Red))],
+            None => vec![
+                Line::raw("Tab/Up/Down: field, Left/Right/Space: change, Enter: save"),
+                Line::raw("Ctrl-S: send digest now, Ctrl-B: bump volume, Esc: cancel"),
+            ],
+        };
+        let hint_area = Rect { x: inner.x, y: inner.y + rows.len() as u16 + 1, width: inner.width, height: 2 }.intersection(inner);
+        frame.render_widget(Paragraph::new(hint), hint_area);
+    }
+
+    fn input(&mut self, input: Input) -> PopupStatus {
+        let mut status = PopupStatus::Continue;
+        match input {
+            Input { key: Key::Esc, .. } => status = PopupStatus::Cancel,
+            Input { key: Key::Char('s'), ctrl: true, .. } => {
+                self.action = DigestAction::Send;
+                status = PopupStatus::Submit;
+            }
+            Input { key: Key::Char('b'), ctrl: true, .. } => {
+                self.action = DigestAction::Bump;
+                status = PopupStatus::Submit;
+            }
+            Input { key: Key::Enter, .. } => {
+                if !self.loaded {
+                    self.error = Some("The digest settings haven't been loaded yet".to_string());
+                } else if self.threshold().parse::<f64>().is_err() {
+                    self.error = Some("The size threshold must be a number".to_string());
+                } else {
+                    self.action = DigestAction::Save;
+                    status = PopupStatus::Submit;
+                }
+            }
+            Input { key: Key::Tab, .. } |
+            Input { key: Key::Down, .. } => self.set_focus((self.focus + 1) % 4),
+            Input { key: Key::Up, .. } => self.set_focus((self.focus + 3) % 4),
+            input => match self.focus {
+                0 => if let Input { key: Key::Left | Key::Right | Key::Char(' '), .. } = input {
+                    self.digests_enabled = !self.digests_enabled;
+                },
+                1 => if let Input { key: Key::Left | Key::Right | Key::Char(' '), .. } = input {
+                    self.digest_send_periodic = !self.digest_send_periodic;
+                },
+                2 => { self.digest_size_threshold.input(input); }
+                _ => match input {
+                    Input { key: Key::Left, .. } => {
+                        self.digest_volume_frequency = (self.digest_volume_frequency + FREQUENCIES.len() - 1) % FREQUENCIES.len();
+                    }
+                    Input { key: Key::Right, .. } |
+                    Input { key: Key::Char(' '), .. } => {
+                        self.digest_volume_frequency = (self.digest_volume_frequency + 1) % FREQUENCIES.len();
+                    }
+                    _input => {}
+                }
+            }
+        }
+
+        status
+    }
+
+    fn paste(&mut self, text: &str) {
+        if self.focus == 2 {
+            popup::paste_line(&mut self.digest_size_threshold, text);
+        }
+    }
+
+    fn submit(&self) -> Option<PopupReqParam> {
+        let mut map = HashMap::new();
+        let param = match self.action {
+            DigestAction::Send => {
+                map.insert("send".to_string(), "true".to_string());
+                PopupReqParam::new(Method::POST, self.url("digest"), map)
+            }
+            DigestAction::Bump => {
+                map.insert("bump".to_string(), "true".to_string());
+                PopupReqParam::new(Method::POST, self.url("digest"), map)
+            }
+            DigestAction::Save => {
+                map.insert("digests_enabled".to_string(), self.digests_enabled.to_string());
+                map.insert("digest_send_periodic".to_string(), self.digest_send_periodic.to_string());
+                map.insert("digest_size_threshold".to_string(), self.threshold());
+                map.insert("digest_volume_frequency".to_string(), FREQUENCIES[self.digest_volume_frequency].to_string());
+                PopupReqParam::new(Method::PATCH, self.url("config"), map)
+            }
+        };
+        Some(param)
+    }
+
+    fn load(&self) -> Vec<PopupReqParam> {
+        vec![PopupReqParam::new_for(Method::GET, self.url("config"), HashMap::new(), ResponseType::PopupData("config".to_string()))]
+    }
+
+    fn loaded(&mut self, response: Response) {
+        if !response.is_success() {
+            self.error = Some(format!("Can't load the digest settings: {}", response.status()));
+            return;
+        }
+        if let Ok(value) = serde_json::from_str::<Value>(&response.text()) {
+            let text = |key: &str| match value.get(key) {
+                Some(Value::String(s)) => s.clone(),
+                Some(Value::Null) | None => "(never)".to_string(),
+                Some(v) => v.to_string(),
+            };
+            self.volume = text("volume");
+            self.next_digest_number = text("next_digest_number");
+            self.last_sent_at = text("digest_last_sent_at");
+            self.digests_enabled = value.get("digests_enabled").and_then(Value::as_bool).unwrap_or_default();
+            self.digest_send_periodic = value.get("digest_send_periodic").and_then(Value::as_bool).unwrap_or_default();
+            self.digest_size_threshold = TextArea::new(vec![text("digest_size_threshold")]);
+            self.digest_size_threshold.set_cursor_line_style(Style::default());
+            if let Some(frequency) = value.get("digest_volume_frequency").and_then(Value::as_str) {
+                self.digest_volume_frequency = FREQUENCIES.iter().position(|f| *f == frequency).unwrap_or(1);
+            }
+            let focus = self.focus;
+            self.set_focus(focus);
+            self.loaded = true;
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use std::collections::HashMap;
+
+use ratatui::{prelude::*, widgets::*};
+use reqwest::{Method, Url};
+use tui_textarea::{TextArea, Input, Key};
+
+use crate::{config::Config, popup::{self, Popup, PopupReqParam, PopupStatus}};
+
+const ACTIONS: [&str; 5] = ["hold", "reject", "discard", "accept", "defer"];
+
+#[derive(Clone)]
+pub struct HeaderMatchAdd<'a> {
+    config: Config,
+    header: TextArea<'a>,
+    pattern: TextArea<'a>,
+    action: usize,
+    focus: usize,
+    error: Option<String>,
+}
+
+impl<'a> HeaderMatchAdd<'a> {
+    pub fn new(config: Config) -> Self {
+        let mut header = TextArea::default();
+        header.set_block(Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(" Header (e.g. X-Spam-Flag) ".to_string())
+            .style(Style::default().fg(Color::Blue)),
+        );
+        let mut pattern = TextArea::default();
+        pattern.set_block(Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(" Pattern (regular expression) ".to_string())
+            .style(Style::default().fg(Color::Blue)),
+        );
+
+        let mut header_match_add = Self {
+            config,
+            header,
+            pattern,
+            action: 0,
+            focus: 0,
+            error: None,
+        };
+        header_match_add.set_focus(0);
+        header_match_add
+    }
+
+    fn set_focus(&mut self, focus: usize) {
+        self.focus = focus;
+        for (i, text_area) in [&mut self.header, &mut self.pattern].into_iter().enumerate() {
+            if i == focus {
+                text_area.set_cursor_style(Style::default().add_modifier(Modifier::REVERSED));
+            } else {
+                text_area.set_cursor_style(Style::default());
+            }
+        }
+    }
+
+    fn validate(&self) -> Option<String> {
+        if self.header.lines()[0].trim().is_empty() {
+            Some("The header must not be empty".to_string())
+        } else if self.pattern.lines()[0].trim().is_empty() {
+            Some("The pattern must not be empty".to_string())
+        } else {
+            None
+        }
+    }
+}
+
+impl Popup for HeaderMatchAdd<'_> {
+    fn render(&mut self, frame: &mut Frame) {
+        let area = popup::area(frame, 80, 10);
+        let chunks = Layout::vertical([Constraint::Length(3), Constraint::Length(3), Constraint::Length(3), Constraint::Length(1)]).split(area);
+
+        frame.render_widget(&self.header, chunks[0]);
+        frame.render_widget(&self.pattern, chunks[1]);
+        let mut title_style = Style::default().fg(Color::Blue);
+        if self.focus == 2 {
+            title_style = title_style.add_modifier(Modifier::REVERSED);
+        }
+        let action = Paragraph::new(format!("< {} >  (Left/Right to change, Enter to add)", ACTIONS[self.action]))
+            .block(Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(Span::styled(" Action ", title_style))
+            .style(Style::default().fg(Color::Blue)),
+        );
+        frame.render_widget(action, chunks[2]);
+
+        let hint = match &self.error {
+            Some(error) => Line::styled(error.clone(), Style::default().fg(Color::Red)),
+            None => Line::raw("Tab/Up/Down: field, Enter: add, Esc: cancel"),
+        };
+        frame.render_widget(Paragraph::new(hint).style(Style::default().fg(Color::Blue)), chunks[3]);
+    }
+
+    fn input(&mut self, input: Input) -> PopupStatus {
+        let mut status = PopupStatus::Continue;
+        match input {
+            Input { key: Key::Esc, .. } => status = PopupStatus::Cancel,
+            Input { key: Key::Enter, .. } => {
+                self.error = self.validate();
+                if self.error.is_none() {
+                    status = PopupStatus::Submit;
+                }
+            }
+            Input { key: Key::Tab, .. } |
+            Input { key: Key::Down, .. } => self.set_focus((self.focus + 1) % 3),
+            Input { key: Key::Up, .. } => self.set_focus((self.focus + 2) % 3),
+            input => match self.focus {
+                0 => { self.header.input(input); }
+                1 => { self.pattern.input(input); }
+                _ => match input {
+                    Input { key: Key::Left, .. } => self.action = (self.action + ACTIONS.len() - 1) % ACTIONS.len(),
+                    Input { key: Key::Right, .. } |
+                    Input { key: Key::Char(' '), .. } => self.action = (self.action + 1) % ACTIONS.len(),
+                    _input => {}
+                }
+            }
+        }
+
+        status
+    }
+
+    fn paste(&mut self, text: &str) {
+        match self.focus {
+            0 => popup::paste_line(&mut self.header, text),
+            1 => popup::paste_line(&mut self.pattern, text),
+            _ => {}
+        }
+    }
+
+    fn submit(&self) -> Option<PopupReqParam> {
+        let url = Url::parse(&format!("{}://{}:{}/3.1/lists/{}/header-matches",
+            self.config.protocol(),
+            self.config.host(),
+            self.config.port(),
+            self.config.list().unwrap().list_id())).unwrap();
+
+        let mut map = HashMap::new();
+        map.insert("header".to_string(), self.header.lines()[0].trim().to_string());
+        map.insert("pattern".to_string(), self.pattern.lines()[0].trim().to_string());
+        map.insert("action".to_string(), ACTIONS[self.action].to_string());
+
+        Some(PopupReqParam::new(Method::POST, url, map))
+    }
+}
@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+
+use ratatui::{prelude::*, widgets::*};
+use reqwest::{Method, Url};
+use tui_textarea::{Input, Key};
+
+use crate::{config::Config, popup::{self, Buttons, Popup, PopupReqParam, PopupStatus}, undo::Undo};
+
+#[derive(Clone)]
+pub struct HeaderMatchDel<'a> {
+    config: Config,
+    paragraph: Paragraph<'a>,
+    buttons: Buttons,
+    // Rules before deleting; a recreated rule is appended, i.e. lands at count - 1
+    count: u32,
+}
+
+impl<'a> HeaderMatchDel<'a> {
+    pub fn new(config: Config, count: u32) -> Self {
+        let text = vec![
+            Line::from(config.header_match().unwrap().description()),
+            Line::raw("Are you sure? Type 'y' or Enter for yes or 'n' or Esc for no"),
+        ];
+        let paragraph = Paragraph::new(text)
+            .block(Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(" Delete Header Filter Rule? ".to_string())
+            .style(Style::default().fg(Color::Blue)),
+        );
+
+        Self {
+            config,
+            paragraph,
+            buttons: Buttons::new(&[("Yes", Key::Enter), ("No", Key::Esc)]),
+            count,
+        }
+    }
+}
+
+impl Popup for HeaderMatchDel<'_> {
+    fn render(&mut self, frame: &mut Frame) {
+        let area = popup::area(frame, 62, 5);
+
+        frame.render_widget(self.paragraph.clone(), area);
+        self.buttons.render(frame, area);
+    }
+
+    fn input(&mut self, input: Input) -> PopupStatus {
+        let mut status = PopupStatus::Continue;
+        match input {
+            Input { key: Key::Esc, .. } |
+            Input { key: Key::Char('n'), .. } |
+            Input { key: Key::Char('N'), .. } => status = PopupStatus::Cancel,
+            Input { key: Key::Enter, .. } |
+            Input { key: Key::Char('y'), .. } |
+            Input { key: Key::Char('Y'), .. } => status = PopupStatus::Submit,
+            _input => {}
+        }
+
+        status
+    }
+
+    fn buttons(&self) -> Option<&Buttons> {
+        Some(&self.buttons)
+    }
+
+    fn submit(&self) -> Option<PopupReqParam> {
+        let url = Url::parse(&format!("{}://{}:{}/3.1/lists/{}/header-matches/{}",
+            self.config.protocol(),
+            self.config.host(),
+            self.config.port(),
+            self.config.list().unwrap().list_id(),
+            self.config.header_match().unwrap().position())).unwrap();
+        let map = HashMap::new();
+
+        Some(PopupReqParam::new(Method::DELETE, url, map))
+    }
+
+    fn undo(&self) -> Option<Undo> {
+        let header_match = self.config.header_match().unwrap();
+        let url = |path: String| Url::parse(&format!("{}://{}:{}/3.1/lists/{}/header-matches{}",
+            self.config.protocol(),
+            self.config.host(),
+            self.config.port(),
+            self.config.list().unwrap().list_id(),
+            path)).unwrap();
+
+        let mut map = HashMap::new();
+        map.insert("header".to_string(), header_match.header());
+        map.insert("pattern".to_string(), header_match.pattern());
+        if let Some(action) = header_match.action() {
+            map.insert("action".to_string(), action);
+        }
+        if let Some(tag) = header_match.tag() {
+            map.insert("tag".to_string(), tag);
+        }
+        let mut params = vec![PopupReqParam::new(Method::POST, url(String::new()), map)
+            .with_label(format!("header filter {}", header_match.description()))];
+
+        let last = self.count.saturating_sub(1);
+        if header_match.position() < last {
+            let mut map = HashMap::new();
+            map.insert("position".to_string(), header_match.position().to_string());
+            params.push(PopupReqParam::new(Method::PATCH, url(format!("/{}", last)), map)
+                .with_label("moving the header filter back".to_string())
+                .if_previous_succeeded());
+        }
+
+        Some(Undo::Restore(format!("deleting header filter {}", header_match.description()), params))
+    }
+}
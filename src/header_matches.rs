@@ -0,0 +1,67 @@
+use serde::{Serialize, Deserialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HeaderMatches {
+    entries: Option<Vec<Entry>>,
+    http_etag: String,
+    start: u32,
+    total_size: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Entry {
+    action: Option<String>,
+    header: String,
+    http_etag: String,
+    pattern: String,
+    position: u32,
+    self_link: String,
+    tag: Option<String>,
+}
+
+impl HeaderMatches {
+    pub fn table(&self) -> (Vec<String>, Vec<Vec<String>>) {
+        let header = ["position", "header", "pattern", "action"].iter().map(|c| c.to_string()).collect();
+        let rows = if let Some(entries) = &self.entries {
+            entries.iter().map(|entry| vec![
+                entry.position.to_string(),
+                entry.header.clone(),
+                entry.pattern.clone(),
+                entry.action.clone().unwrap_or("(default)".to_string()),
+            ]).collect()
+        } else {
+            Vec::new()
+        };
+        (header, rows)
+    }
+
+    pub fn entries(&self) -> Option<Vec<Entry>> {
+        self.entries.clone()
+    }
+}
+
+impl Entry {
+    pub fn description(&self) -> String {
+        format!("{}: {}", self.header, self.pattern)
+    }
+
+    pub fn position(&self) -> u32 {
+        self.position
+    }
+
+    pub fn header(&self) -> String {
+        self.header.clone()
+    }
+
+    pub fn pattern(&self) -> String {
+        self.pattern.clone()
+    }
+
+    pub fn action(&self) -> Option<String> {
+        self.action.clone()
+    }
+
+    pub fn tag(&self) -> Option<String> {
+        self.tag.clone()
+    }
+}
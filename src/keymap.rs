@@ -0,0 +1,402 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+
+use crate::{ui::MenuItem, Action};
+
+// keys.json next to config.json, e.g.
+// {
+//     "preset": "vim",
+//     "global": { "backspace": "delete", "x": null },
+//     "views": { "members": { "x": "mass_delete" } }
+// }
+// The preset is "default", "vim" or "arrows". Bindings in "global" replace the preset's,
+// those in "views" only apply in one view; null unbinds a key.
+#[derive(Deserialize, Default)]
+struct File {
+    #[serde(default)]
+    preset: Option<String>,
+    #[serde(default)]
+    global: HashMap<String, Option<String>>,
+    #[serde(default)]
+    views: HashMap<String, HashMap<String, Option<String>>>,
+}
+
+type Key = (KeyCode, KeyModifiers);
+
+pub struct Keymap {
+    global: HashMap<Key, Action>,
+    // None unbinds a key the global map has
+    views: HashMap<String, HashMap<Key, Option<Action>>>,
+}
+
+const DEFAULT: &[(&str, &str)] = &[
+    ("ctrl-z", "undo"),
+    ("q", "quit"), ("Q", "quit"),
+    ("d", "domains"), ("D", "domains"),
+    ("l", "lists"), ("L", "lists"),
+    ("m", "members"), ("M", "members"),
+    ("s", "messages"), ("S", "messages"),
+    ("y", "system"), ("Y", "system"),
+    ("/", "search"),
+    ("n", "next_match"),
+    ("N", "prev_match"),
+    ("j", "down"), ("J", "down"), ("down", "down"),
+    ("k", "up"), ("K", "up"), ("up", "up"),
+    ("t", "list_templates"),
+    ("T", "domain_templates"),
+    ("h", "header_matches"), ("H", "header_matches"),
+    ("v", "archivers"), ("V", "archivers"),
+    ("i", "aliases"), ("I", "aliases"),
+    ("g", "digest"), ("G", "digest"),
+    ("b", "bounces"), ("B", "bounces"),
+    ("e", "inject"), ("E", "inject"),
+    ("[", "move_up"),
+    ("]", "move_down"),
+    ("u", "unselect"),
+    ("U", "undo"),
+    ("a", "add"), ("A", "add"),
+    ("f", "find"), ("F", "find"),
+    ("space", "mark"),
+    // Not backspace, which is too easily hit while meaning to edit a search or field
+    ("x", "delete"), ("X", "delete"),
+    ("ctrl-x", "mass_delete"),
+    ("r", "sync"), ("R", "sync"),
+    ("c", "copy"), ("C", "copy"),
+    ("w", "backup_restore"), ("W", "backup_restore"),
+    ("p", "audit"), ("P", "audit"),
+    ("enter", "open"),
+    ("1", "sort_1"), ("2", "sort_2"), ("3", "sort_3"),
+    ("4", "sort_4"), ("5", "sort_5"), ("6", "sort_6"),
+    ("7", "sort_7"), ("8", "sort_8"), ("9", "sort_9"),
+];
+
+// Changes to the default preset; the remaining default keys stay, e.g. the view letters
+const VIM: &[(&str, Option<&str>)] = &[
+    ("u", Some("undo")),
+    ("esc", Some("unselect")),
+    ("ctrl-n", Some("down")),
+    ("ctrl-p", Some("up")),
+    ("o", Some("open")),
+];
+
+const ARROWS: &[(&str, Option<&str>)] = &[
+    ("j", None), ("J", None),
+    ("k", None), ("K", None),
+    ("delete", Some("delete")),
+    ("esc", Some("unselect")),
+    ("left", Some("prev_view")),
+    ("right", Some("next_view")),
+];
+
+pub fn action(name: &str) -> Option<Action> {
+    let action = match name {
+        "quit" => Action::Quit,
+        "domains" => Action::Domains,
+        "lists" => Action::Lists,
+        "members" => Action::Members,
+        "messages" => Action::Messages,
+        "system" => Action::System,
+        "search" => Action::Search,
+        "next_match" => Action::NextMatch,
+        "prev_match" => Action::PrevMatch,
+        "down" => Action::Down,
+        "up" => Action::Up,
+        "list_templates" => Action::ListTemplates,
+        "domain_templates" => Action::DomainTemplates,
+        "header_matches" => Action::HeaderMatches,
+        "archivers" => Action::Archivers,
+        "aliases" => Action::Aliases,
+        "digest" => Action::Digest,
+        "bounces" => Action::Bounces,
+        "inject" => Action::Inject,
+        "move_up" => Action::MoveUp,
+        "move_down" => Action::MoveDown,
+        "unselect" => Action::Unselect,
+        "undo" => Action::Undo,
+        "add" => Action::Add,
+        "find" => Action::Find,
+        "mark" => Action::Mark,
+        "delete" => Action::Delete,
+        "mass_delete" => Action::MassDelete,
+        "sync" => Action::Sync,
+        "copy" => Action::Copy,
+        "clone_config" => Action::CloneConfig,
+        "backup_restore" => Action::BackupRestore,
+        "audit" => Action::Audit,
+        "open" => Action::Open,
+        "prev_view" => Action::PrevView,
+        "next_view" => Action::NextView,
+        "none" => Action::None,
+        // sort_1 to sort_9 sort by the column
+        _ => match name.strip_prefix("sort_").and_then(|n| n.parse::<usize>().ok()) {
+            Some(n @ 1..=9) => Action::SortBy(n - 1),
+            _ => return None,
+        },
+    };
+    Some(action)
+}
+
+// "x", "X", "ctrl-z", "alt-enter", "shift-tab", "f5", ...
+pub fn key(name: &str) -> Option<Key> {
+    let mut modifiers = KeyModifiers::NONE;
+    let mut rest = name;
+    // A lone "-" is the key itself, not a modifier
+    while let Some((modifier, key)) = rest.split_once('-').filter(|(_, key)| !key.is_empty()) {
+        match modifier.to_lowercase().as_str() {
+            "ctrl" => modifiers |= KeyModifiers::CONTROL,
+            "alt" => modifiers |= KeyModifiers::ALT,
+            "shift" => modifiers |= KeyModifiers::SHIFT,
+            _ => break,
+        }
+        rest = key;
+    }
+    let mut chars = rest.chars();
+    let code = match (chars.next(), chars.next()) {
+        (Some(c), None) if modifiers.contains(KeyModifiers::SHIFT) => KeyCode::Char(c.to_ascii_uppercase()),
+        (Some(c), None) => KeyCode::Char(c),
+        _ => match rest.to_lowercase().as_str() {
+            "enter" => KeyCode::Enter,
+            "esc" => KeyCode::Esc,
+            "space" => KeyCode::Char(' '),
+            "tab" if modifiers.contains(KeyModifiers::SHIFT) => KeyCode::BackTab,
+            "tab" => KeyCode::Tab,
+            "backtab" => KeyCode::BackTab,
+            "backspace" => KeyCode::Backspace,
+            "delete" => KeyCode::Delete,
+            "insert" => KeyCode::Insert,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            f => match f.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                Some(n @ 1..=12) => KeyCode::F(n),
+                _ => return None,
+            },
+        },
+    };
+    Some(normalize(code, modifiers))
+}
+
+// Terminals report "X" with or without shift, so a char's case is all that counts
+fn normalize(code: KeyCode, modifiers: KeyModifiers) -> Key {
+    let modifiers = modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT);
+    match code {
+        KeyCode::Char(_) |
+        KeyCode::BackTab => (code, modifiers - KeyModifiers::SHIFT),
+        _ => (code, modifiers),
+    }
+}
+
+fn view(name: &str) -> Option<MenuItem> {
+    let view = match name {
+        "domains" => MenuItem::Domains,
+        "lists" => MenuItem::Lists,
+        "members" => MenuItem::Members,
+        "messages" => MenuItem::Messages,
+        "system" => MenuItem::System,
+        "templates" => MenuItem::Templates,
+        "header_matches" => MenuItem::HeaderMatches,
+        "archivers" => MenuItem::Archivers,
+        "queue" => MenuItem::Queue,
+        "bounces" => MenuItem::Bounces,
+        "audit" => MenuItem::Audit,
+        _ => return None,
+    };
+    Some(view)
+}
+
+fn binding(key_name: &str, action_name: Option<&str>) -> Result<(Key, Option<Action>), String> {
+    let key = key(key_name).ok_or(format!("unknown key \"{}\"", key_name))?;
+    match action_name {
+        Some(name) => match action(name) {
+            Some(action) => Ok((key, Some(action))),
+            None => Err(format!("unknown action \"{}\" for \"{}\"", name, key_name)),
+        },
+        None => Ok((key, None)),
+    }
+}
+
+impl Keymap {
+    pub fn preset(name: &str) -> Result<Self, String> {
+        let mut global = HashMap::new();
+        for (key_name, action_name) in DEFAULT.iter().copied() {
+            let (key, action) = binding(key_name, Some(action_name))?;
+            global.extend(action.map(|action| (key, action)));
+        }
+        let changes: &[(&str, Option<&str>)] = match name {
+            "default" => &[],
+            "vim" => VIM,
+            "arrows" => ARROWS,
+            _ => return Err(format!("unknown preset \"{}\", use default, vim or arrows", name)),
+        };
+        let mut keymap = Self {
+            global,
+            views: HashMap::new(),
+        };
+        for (key_name, action_name) in changes {
+            keymap.bind(binding(key_name, *action_name)?);
+        }
+        Ok(keymap)
+    }
+
+    // Without keys.json it's the default preset; a broken one is reported, so the caller
+    // can fall back to the default and tell why
+    pub fn load(config_dir: &Path) -> Result<Self, String> {
+        let path = config_dir.join("keys.json");
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Self::preset("default"),
+            Err(e) => return Err(format!("Can't read {}: {}", path.display(), e)),
+        };
+        let file: File = serde_json::from_str(&text).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let mut keymap = Self::preset(file.preset.as_deref().unwrap_or("default"))
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+        for (key_name, action_name) in &file.global {
+            keymap.bind(binding(key_name, action_name.as_deref()).map_err(|e| format!("{}: {}", path.display(), e))?);
+        }
+        for (view_name, bindings) in &file.views {
+            if view(view_name).is_none() {
+                return Err(format!("{}: unknown view \"{}\"", path.display(), view_name));
+            }
+            let view = keymap.views.entry(view_name.clone()).or_default();
+            for (key_name, action_name) in bindings {
+                let (key, action) = binding(key_name, action_name.as_deref()).map_err(|e| format!("{}: {}", path.display(), e))?;
+                view.insert(key, action);
+            }
+        }
+        Ok(keymap)
+    }
+
+    fn bind(&mut self, (key, action): (Key, Option<Action>)) {
+        match action {
+            Some(action) => self.global.insert(key, action),
+            None => self.global.remove(&key),
+        };
+    }
+
+    // Bindings of the view come first, then the global ones
+    pub fn action(&self, view: MenuItem, key: KeyEvent) -> Action {
+        let key = normalize(key.code, key.modifiers);
+        let in_view = self.views.iter()
+            .find(|(name, _)| self::view(name) == Some(view))
+            .and_then(|(_, bindings)| bindings.get(&key));
+        match in_view {
+            Some(action) => action.clone().unwrap_or(Action::None),
+            None => self.global.get(&key).cloned().unwrap_or(Action::None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    use super::{key, Keymap};
+    use crate::{ui::MenuItem, Action};
+
+    fn press(keymap: &Keymap, view: MenuItem, code: KeyCode, modifiers: KeyModifiers) -> Action {
+        keymap.action(view, KeyEvent::new(code, modifiers))
+    }
+
+    fn load(json: &str) -> Result<Keymap, String> {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("keys.json"), json).unwrap();
+        Keymap::load(dir.path())
+    }
+
+    #[test]
+    fn modifiers() {
+        assert_eq!(key("x"), Some((KeyCode::Char('x'), KeyModifiers::NONE)));
+        assert_eq!(key("ctrl-z"), Some((KeyCode::Char('z'), KeyModifiers::CONTROL)));
+        assert_eq!(key("Ctrl-Alt-x"), Some((KeyCode::Char('x'), KeyModifiers::CONTROL | KeyModifiers::ALT)));
+        assert_eq!(key("alt-enter"), Some((KeyCode::Enter, KeyModifiers::ALT)));
+        assert_eq!(key("shift-up"), Some((KeyCode::Up, KeyModifiers::SHIFT)));
+        assert_eq!(key("f5"), Some((KeyCode::F(5), KeyModifiers::NONE)));
+    }
+
+    #[test]
+    fn dash() {
+        assert_eq!(key("-"), Some((KeyCode::Char('-'), KeyModifiers::NONE)));
+        assert_eq!(key("ctrl--"), Some((KeyCode::Char('-'), KeyModifiers::CONTROL)));
+    }
+
+    #[test]
+    fn shift() {
+        assert_eq!(key("shift-x"), key("X"));
+        assert_eq!(key("X"), Some((KeyCode::Char('X'), KeyModifiers::NONE)));
+        assert_eq!(key("shift-tab"), Some((KeyCode::BackTab, KeyModifiers::NONE)));
+        assert_eq!(key("shift-tab"), key("backtab"));
+    }
+
+    #[test]
+    fn unknown_keys() {
+        assert_eq!(key(""), None);
+        assert_eq!(key("f13"), None);
+        assert_eq!(key("hyper-x"), None);
+        assert_eq!(key("ctrl-nope"), None);
+    }
+
+    #[test]
+    fn default_preset() {
+        let keymap = Keymap::preset("default").unwrap();
+        assert!(matches!(press(&keymap, MenuItem::Members, KeyCode::Char('x'), KeyModifiers::NONE), Action::Delete));
+        // Terminals may or may not report shift with upper case letters
+        assert!(matches!(press(&keymap, MenuItem::Members, KeyCode::Char('X'), KeyModifiers::SHIFT), Action::Delete));
+        assert!(matches!(press(&keymap, MenuItem::Members, KeyCode::Char('x'), KeyModifiers::CONTROL), Action::MassDelete));
+        assert!(matches!(press(&keymap, MenuItem::Members, KeyCode::Backspace, KeyModifiers::NONE), Action::None));
+        assert!(matches!(press(&keymap, MenuItem::Lists, KeyCode::Char('3'), KeyModifiers::NONE), Action::SortBy(2)));
+    }
+
+    #[test]
+    fn presets() {
+        let vim = Keymap::preset("vim").unwrap();
+        assert!(matches!(press(&vim, MenuItem::Lists, KeyCode::Char('u'), KeyModifiers::NONE), Action::Undo));
+        assert!(matches!(press(&vim, MenuItem::Lists, KeyCode::Char('j'), KeyModifiers::NONE), Action::Down));
+        let arrows = Keymap::preset("arrows").unwrap();
+        assert!(matches!(press(&arrows, MenuItem::Lists, KeyCode::Char('j'), KeyModifiers::NONE), Action::None));
+        assert!(matches!(press(&arrows, MenuItem::Lists, KeyCode::Right, KeyModifiers::NONE), Action::NextView));
+        assert!(Keymap::preset("emacs").is_err());
+    }
+
+    #[test]
+    fn load_without_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let keymap = Keymap::load(dir.path()).unwrap();
+        assert!(matches!(press(&keymap, MenuItem::Lists, KeyCode::Char('q'), KeyModifiers::NONE), Action::Quit));
+    }
+
+    #[test]
+    fn load_bindings() {
+        let keymap = load(r#"{
+            "preset": "vim",
+            "global": { "backspace": "delete", "q": null },
+            "views": { "members": { "x": null, "ctrl-d": "mass_delete" } }
+        }"#).unwrap();
+        assert!(matches!(press(&keymap, MenuItem::Lists, KeyCode::Backspace, KeyModifiers::NONE), Action::Delete));
+        assert!(matches!(press(&keymap, MenuItem::Lists, KeyCode::Char('q'), KeyModifiers::NONE), Action::None));
+        assert!(matches!(press(&keymap, MenuItem::Lists, KeyCode::Char('x'), KeyModifiers::NONE), Action::Delete));
+        assert!(matches!(press(&keymap, MenuItem::Members, KeyCode::Char('x'), KeyModifiers::NONE), Action::None));
+        assert!(matches!(press(&keymap, MenuItem::Members, KeyCode::Char('d'), KeyModifiers::CONTROL), Action::MassDelete));
+        assert!(matches!(press(&keymap, MenuItem::Lists, KeyCode::Char('d'), KeyModifiers::CONTROL), Action::None));
+    }
+
+    #[test]
+    fn load_errors() {
+        let error = |json| load(json).err().unwrap();
+        assert!(error(r#"{ "global": { "hyper-x": "quit" } }"#).contains("unknown key \"hyper-x\""));
+        assert!(error(r#"{ "global": { "x": "explode" } }"#).contains("unknown action \"explode\""));
+        assert!(error(r#"{ "views": { "nowhere": {} } }"#).contains("unknown view \"nowhere\""));
+        assert!(error(r#"{ "preset": "emacs" }"#).contains("unknown preset \"emacs\""));
+        assert!(error("{").contains("keys.json"));
+    }
+}
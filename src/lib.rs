@@ -1,13 +1,15 @@
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use color_eyre::eyre::Result;
 use directories::ProjectDirs;
-use clap::{command, arg, value_parser};
-use crossterm::event::KeyCode::{self, Char};
+use clap::{command, arg, value_parser, Command};
+use crossterm::event::{MouseButton, MouseEventKind};
 use member_del::MemberDel;
 use request::ReqType;
 use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
-use reqwest::Client;
+use reqwest::{Client, Method, Url};
 use serde_json::value::Value;
+use tui_textarea::{Input, Key};
 
 mod config;
 mod tui;
@@ -23,7 +25,38 @@ mod list_add;
 mod list_del;
 mod member_add;
 mod member_del;
+mod member_find;
+mod member_mass_del;
+mod addresses;
+mod roster_sync;
+mod member_sync;
+mod member_copy;
+mod config_clone;
+mod backup;
+mod list_backup;
+mod audit;
+mod audit_detail;
 mod message_mod;
+mod templates;
+mod template_edit;
+mod template_reset;
+mod header_matches;
+mod header_match_add;
+mod header_match_del;
+mod archivers;
+mod list_detail;
+mod alias_edit;
+mod digest;
+mod system;
+mod timestamp;
+mod queue;
+mod queue_file;
+mod queue_inject;
+mod qfile;
+mod bounces;
+mod bounce_mod;
+mod undo;
+mod keymap;
 
 use config::Config;
 use tui::{Tui, Event};
@@ -33,11 +66,37 @@ use domains::Domains;
 use lists::Lists;
 use members::Members;
 use messages::Messages;
-use popup::{Popup, PopupStatus};
+use popup::{Popup, PopupStatus, PopupReqParam};
 use list_add::ListAdd;
 use list_del::ListDel;
 use member_add::MemberAdd;
+use member_find::MemberFind;
+use member_mass_del::MemberMassDel;
+use member_sync::MemberSync;
+use member_copy::MemberCopy;
+use config_clone::ConfigClone;
+use list_backup::ListBackup;
+use audit::Audit;
+use audit_detail::AuditDetail;
 use message_mod::MessageMod;
+use templates::Templates;
+use template_edit::TemplateEdit;
+use template_reset::TemplateReset;
+use header_matches::HeaderMatches;
+use header_match_add::HeaderMatchAdd;
+use header_match_del::HeaderMatchDel;
+use archivers::Archivers;
+use list_detail::ListDetail;
+use alias_edit::AliasEdit;
+use digest::Digest;
+use system::System;
+use queue::Queue;
+use queue_file::QueueFile;
+use queue_inject::QueueInject;
+use bounces::Bounces;
+use bounce_mod::BounceMod;
+use undo::{Undo, UndoStack};
+use keymap::Keymap;
 
 #[derive(Clone)]
 pub enum Action {
@@ -48,12 +107,49 @@ pub enum Action {
     Lists,
     Members,
     Messages,
+    System,
+    Queue(String),
+    // The queue listing again, with the headers read from its files
+    QueueHeaders(Queue),
+    Inject,
+    // Injects the selected queue file back into the queue it came from
+    Requeue,
+    Bounces,
+    ListTemplates,
+    DomainTemplates,
+    HeaderMatches,
+    Archivers,
+    Aliases,
+    Digest,
     PopupSubmit,
+    Status(String),
     Unselect,
+    Mark,
+    // A click on a row, by its position among the shown rows
+    Click(usize),
     Up,
     Down,
+    MoveUp,
+    MoveDown,
+    Search,
+    NextMatch,
+    PrevMatch,
+    SortBy(usize),
     Add,
+    Find,
     Delete,
+    MassDelete,
+    Sync,
+    Copy,
+    CloneConfig,
+    BackupRestore,
+    Backup(PathBuf),
+    Audit,
+    Undo,
+    // Sent once a destructive popup's requests went through
+    Undoable(Undo),
+    PrevView,
+    NextView,
     Open,
     RequestResponse(Response),
     None,
@@ -67,6 +163,16 @@ pub struct Marge {
     lists: Option<Lists>,
     members: Option<Members>,
     messages: Option<Messages>,
+    templates: Option<Templates>,
+    header_matches: Option<HeaderMatches>,
+    archivers: Option<Archivers>,
+    system: System,
+    queue: Option<Queue>,
+    bounces: Option<Bounces>,
+    audit: Option<Audit>,
+    undo: UndoStack,
+    keymap: Keymap,
+    ticks: u32,
     should_quit: bool,
     action_tx: UnboundedSender<Action>,
     action_rx: UnboundedReceiver<Action>,
@@ -86,7 +192,7 @@ impl Marge {
         }
         let mut config = Config::new();
         if let Some(ref config_dir) = config_dir {
-          if let Ok(lconfig) = Config::new_from_file(&config_dir) {
+          if let Ok(lconfig) = Config::new_from_file(config_dir) {
             config = lconfig;
           } else {
             config_changed = true;
@@ -98,10 +204,28 @@ impl Marge {
         let lists = None;
         let members = None;
         let messages = None;
+        let templates = None;
+        let header_matches = None;
+        let archivers = None;
+        let system = System::default();
+        let queue = None;
+        let bounces = None;
+        let audit = None;
+        let undo = UndoStack::default();
+        let ticks = 0;
         let should_quit = false;
         let (action_tx, action_rx) = mpsc::unbounded_channel();
         let tui = Tui::new()?;
-        let ui = Ui::new();
+        let mut ui = Ui::new();
+        // A broken keys.json shouldn't lock anyone out, so fall back to the default keys
+        let keymap = match config_dir.as_deref().map(Keymap::load) {
+            Some(Ok(keymap)) => keymap,
+            Some(Err(e)) => {
+                ui.set_status(format!("Using the default keys: {}", e));
+                Keymap::preset("default").unwrap()
+            }
+            None => Keymap::preset("default").unwrap(),
+        };
         let client = reqwest::Client::new();
         let response_t = None;
         let popup = None;
@@ -114,6 +238,16 @@ impl Marge {
             lists,
             members,
             messages,
+            templates,
+            header_matches,
+            archivers,
+            system,
+            queue,
+            bounces,
+            audit,
+            undo,
+            keymap,
+            ticks,
             should_quit,
             action_tx,
             action_rx,
@@ -145,6 +279,29 @@ impl Marge {
             .required(false)
             .value_parser(value_parser!(i32)) 
         ])
+        .subcommand(Command::new("sync")
+            .about("Synchronizes the members of a list with a file (CSV or one address per line), e.g. from cron")
+            .args([
+              arg!(<LIST> "list to synchronize, e.g. staff@example.org"),
+              arg!(<FILE> "authoritative source of the list's members")
+                .value_parser(value_parser!(PathBuf)),
+              arg!(--"never-remove" "only subscribe missing addresses, never unsubscribe anybody"),
+              arg!(-n --"dry-run" "only show what would change"),
+            ]))
+        .subcommand(Command::new("backup")
+            .about("Saves a list's settings, members, bans, header filters and templates to a JSON file")
+            .args([
+              arg!(<LIST> "list to back up, e.g. staff@example.org"),
+              arg!(<FILE> "file to write the backup to")
+                .value_parser(value_parser!(PathBuf)),
+            ]))
+        .subcommand(Command::new("restore")
+            .about("Recreates a list from a backup, on this server or another one")
+            .args([
+              arg!(<FILE> "backup to restore")
+                .value_parser(value_parser!(PathBuf)),
+              arg!(-l --list <LIST> "restore under another name than the backed up list's"),
+            ]))
         .try_get_matches();
 
         match matches {
@@ -155,7 +312,7 @@ impl Marge {
                 Ok(())
               },
               _ => {
-                eprintln!("An error occured while parsing arguments: {}", e.to_string());
+                eprintln!("An error occured while parsing arguments: {}", e);
                 Err(Box::new(clap::Error::new(e.kind())))?
               },     
             },
@@ -182,6 +339,24 @@ impl Marge {
                     self.config_changed = true;
                 }
 
+                match matches.subcommand() {
+                    Some(("sync", sync)) => return roster_sync::run(&mut self.client,
+                        &self.config,
+                        sync.get_one::<String>("LIST").unwrap(),
+                        sync.get_one::<PathBuf>("FILE").unwrap(),
+                        sync.get_flag("never-remove"),
+                        sync.get_flag("dry-run")).await,
+                    Some(("backup", backup)) => return backup::run_backup(&mut self.client,
+                        &self.config,
+                        backup.get_one::<String>("LIST").unwrap(),
+                        backup.get_one::<PathBuf>("FILE").unwrap()).await,
+                    Some(("restore", restore)) => return backup::run_restore(&mut self.client,
+                        &self.config,
+                        restore.get_one::<PathBuf>("FILE").unwrap(),
+                        restore.get_one::<String>("list")).await,
+                    _ => {}
+                }
+
                 self.tui.enter()?;
 
                 self.action_tx.send(Action::Domains)?;
@@ -191,20 +366,44 @@ impl Marge {
                     match e {
 //                      tui::Event::Quit => self.action_tx.send(Action::Quit)?,
                         tui::Event::Render => self.action_tx.send(Action::Render)?,
+                        tui::Event::Tick => self.action_tx.send(Action::Tick)?,
+                        // Popups are laid out relative to the screen, so drawing again is all it takes
+                        tui::Event::Resize(_, _) => self.action_tx.send(Action::Render)?,
                         tui::Event::Key(k_event) => {
-                            if let Some(_) = &self.popup {
-                                match self.popup.as_mut().unwrap().input(k_event.into()) {
-                                    PopupStatus::Cancel => self.popup = None,
-                                    PopupStatus::Submit => self.action_tx.send(Action::PopupSubmit)?,
-                                    PopupStatus::Continue => {
-                                        //Nothing to do: popup wants to contine, so let's start next iteration
-                                    }
-                                }
+                            if self.ui.searching() {
+                                self.ui.search_input(k_event);
+                                self.sync_selection();
+                            } else if let Some(popup) = &mut self.popup {
+                                let status = popup.input(k_event.into());
+                                self.popup_status(status)?;
                             } else {
                                 let action = self.get_action(e);
                                 self.action_tx.send(action.clone())?;
                             }
                         }
+                        tui::Event::Paste(text) => {
+                            if self.ui.searching() {
+                                self.ui.search_paste(&text);
+                                self.sync_selection();
+                            } else if let Some(popup) = &mut self.popup {
+                                popup.paste(&text);
+                            }
+                        }
+                        tui::Event::Mouse(mouse) => {
+                            if let Some(popup) = &mut self.popup {
+                                // The wheel scrolls like the arrow keys do in most popups
+                                let status = match mouse.kind {
+                                    MouseEventKind::Down(MouseButton::Left) => popup.click(mouse.column, mouse.row),
+                                    MouseEventKind::ScrollDown => popup.input(Input { key: Key::Down, ..Default::default() }),
+                                    MouseEventKind::ScrollUp => popup.input(Input { key: Key::Up, ..Default::default() }),
+                                    _ => PopupStatus::Continue,
+                                };
+                                self.popup_status(status)?;
+                            } else if !self.ui.searching() {
+                                let action = self.get_action(e);
+                                self.action_tx.send(action)?;
+                            }
+                        }
                         _ => {}
                     }
 
@@ -213,8 +412,8 @@ impl Marge {
                         if let Action::Render = action {
                             self.tui.draw(|f| {
                                 self.ui.render(f);
-                                if let Some(_) = &self.popup {
-                                    self.popup.as_mut().unwrap().render(f);
+                                if let Some(popup) = &mut self.popup {
+                                    popup.render(f);
                                 }
                             })?;
                         }
@@ -229,7 +428,10 @@ impl Marge {
                         self.config.set_list(None);
                         self.config.set_member(None);
                         self.config.set_message(None);
-                        self.config.save(&config_dir);
+                        self.config.set_template(None);
+                        self.config.set_header_match(None);
+                        self.config.set_queue_file(None);
+                        self.config.save(config_dir);
                     }
                 }
 
@@ -238,198 +440,547 @@ impl Marge {
         }
     }
 
+    fn popup_status(&mut self, status: PopupStatus) -> Result<()> {
+        match status {
+            PopupStatus::Cancel => self.popup = None,
+            PopupStatus::Submit => self.action_tx.send(Action::PopupSubmit)?,
+            PopupStatus::Action(action) => {
+                self.popup = None;
+                self.action_tx.send(action)?;
+            }
+            PopupStatus::Edit(path) => self.edit(&path)?,
+            PopupStatus::Load => if let Some(popup) = &self.popup {
+                let params = popup.load();
+                self.fetch_all(params);
+            }
+            PopupStatus::Continue => {
+                //Nothing to do: popup wants to contine, so let's start next iteration
+            }
+        }
+        Ok(())
+    }
+
+    // Hands the terminal over to the user's editor and takes it back afterwards
+    fn edit(&mut self, path: &Path) -> Result<()> {
+        let editor = std::env::var("VISUAL")
+            .or_else(|_| std::env::var("EDITOR"))
+            .unwrap_or("vi".to_string());
+        let mut words = editor.split_whitespace();
+        let program = words.next().unwrap_or("vi");
+
+        self.tui.exit()?;
+        let result = std::process::Command::new(program)
+            .args(words)
+            .arg(path)
+            .status();
+        self.tui.enter()?;
+        self.tui.clear()?;
+
+        match result {
+            Ok(status) if status.success() => {}
+            Ok(status) => self.ui.set_status(format!("{} exited with {}", editor, status)),
+            Err(e) => self.ui.set_status(format!("Can't run {}: {}", editor, e)),
+        }
+        Ok(())
+    }
+
     fn get_action(&mut self, event: Event) -> Action {
         match event {
             Event::Error => Action::None,
             Event::Tick => Action::Tick,
             Event::Render => Action::Render,
-            Event::Key(key) =>
-                match key.code {
-                    Char('q') |
-                    Char('Q') => Action::Quit,
-                    Char('d') |
-                    Char('D') => Action::Domains,
-                    Char('l') |
-                    Char('L') => Action::Lists,
-                    Char('m') |
-                    Char('M') => Action::Members,
-                    Char('s') |
-                    Char('S') => Action::Messages,
-                    Char('j') |
-                    Char('J') |
-                    KeyCode::Down => Action::Down,
-                    Char('k') |
-                    Char('K') |
-                    KeyCode::Up => Action::Up,
-                    Char('u') |
-                    Char('U') => Action::Unselect,
-                    Char('a') |
-                    Char('A') => Action::Add,
-                    Char('x') |
-                    Char('X') |
-                    KeyCode::Backspace => Action::Delete,
-                    KeyCode::Enter => Action::Open,
+            Event::Key(key) => self.keymap.action(self.ui.active_menu_item(), key),
+            Event::Mouse(mouse) =>
+                match mouse.kind {
+                    // In the order of the tabs; Configure has nothing to show yet
+                    MouseEventKind::Down(MouseButton::Left) => match self.ui.tab_at(mouse.column, mouse.row) {
+                        Some(0) => Action::Domains,
+                        Some(1) => Action::Lists,
+                        Some(2) => Action::Members,
+                        Some(3) => Action::Messages,
+                        Some(4) => Action::System,
+                        Some(6) => Action::Quit,
+                        Some(_) => Action::None,
+                        None => self.ui.row_at(mouse.column, mouse.row).map_or(Action::None, Action::Click),
+                    }
+                    MouseEventKind::ScrollDown => Action::Down,
+                    MouseEventKind::ScrollUp => Action::Up,
                     _ => Action::None,
                 }
             _ => Action::None       
         }
     }
 
+    fn fetch(&self, req_t: ReqType, response_t: ResponseType) {
+        let action_tx = self.action_tx.clone();
+        let mut client = self.client.clone();
+        let config = self.config.clone();
+        tokio::spawn(async move {
+            let resp = request::request(&mut client, req_t, &config).await;
+            let response = Response::new(resp, response_t).await;
+            let _ = action_tx.send(Action::RequestResponse(response));
+        });
+    }
+
+    // Reading the queue files may take a while, the listing shows without their headers until then
+    fn read_queue_headers(&self, mut queue: Queue) {
+        let action_tx = self.action_tx.clone();
+        tokio::task::spawn_blocking(move || {
+            if let Some(headers) = queue.read_headers() {
+                queue.set_headers(headers);
+                let _ = action_tx.send(Action::QueueHeaders(queue));
+            }
+        });
+    }
+
+    // The action reloading the view a response was shown in
+    fn refresh_action(response_t: &ResponseType) -> Option<Action> {
+        match response_t {
+            ResponseType::Domains => Some(Action::Domains),
+            ResponseType::Lists => Some(Action::Lists),
+            ResponseType::Members => Some(Action::Members),
+            ResponseType::Messages => Some(Action::Messages),
+            ResponseType::Templates(scope) => if scope.starts_with("domains/") {
+                Some(Action::DomainTemplates)
+            } else {
+                Some(Action::ListTemplates)
+            },
+            ResponseType::HeaderMatches => Some(Action::HeaderMatches),
+            ResponseType::Archivers => Some(Action::Archivers),
+            ResponseType::System(_) => Some(Action::System),
+            ResponseType::Queue(name) => Some(Action::Queue(name.clone())),
+            ResponseType::Bounces => Some(Action::Bounces),
+            ResponseType::Audit => Some(Action::Audit),
+            ResponseType::Preferences(_) |
+            ResponseType::Popup |
+            ResponseType::PopupData(_) => None,
+        }
+    }
+
+    // Sends the requests in order, stops at the first failure and reloads the current view afterwards.
+    // The undo is only offered if all requests succeeded.
+    fn send(&mut self, params: Vec<PopupReqParam>, undo: Option<Undo>) {
+        let action_tx = self.action_tx.clone();
+        let mut client = self.client.clone();
+        let config = self.config.clone();
+        let popup_response_t = params.last().map_or(ResponseType::Popup, |param| param.response_type());
+        if popup_response_t == ResponseType::Members {
+            self.ui.set_active_menu_item(MenuItem::Members);
+        }
+        let refresh = self.response_t.as_ref().and_then(Self::refresh_action);
+        tokio::spawn(async move {
+            let mut failed = false;
+            for param in params {
+                let param_response_t = param.response_type();
+                let resp = request::request(&mut client, ReqType::Popup(param), &config).await;
+                let response = Response::new(resp, param_response_t).await;
+                failed = !response.is_success();
+                let _ = action_tx.send(Action::RequestResponse(response));
+                if failed {
+                    break;
+                }
+            }
+            if let Some(undo) = undo.filter(|_| !failed) {
+                let _ = action_tx.send(Action::Undoable(undo));
+            }
+            // Popups showing their result in a view don't need that view reloaded
+            if popup_response_t != ResponseType::Popup {
+                return;
+            }
+            if let Some(refresh) = refresh {
+                let _ = action_tx.send(refresh);
+            }
+        });
+    }
+
+    // Sends all requests even if some fail, unless a required one did, and sums up the failures in the status bar.
+    // The undo is offered if anything went through; undoing the failed parts fails harmlessly.
+    fn send_batch(&mut self, params: Vec<PopupReqParam>, note: Option<String>, undo: Option<Undo>) {
+        let action_tx = self.action_tx.clone();
+        let mut client = self.client.clone();
+        let config = self.config.clone();
+        let refresh = self.response_t.as_ref().and_then(Self::refresh_action);
+        self.ui.set_status(format!("Sending {} requests...", params.len()));
+        tokio::spawn(async move {
+            let total = params.len();
+            let mut failures = Vec::new();
+            let mut skipped = Vec::new();
+            let mut previous_succeeded = true;
+            let mut aborted = false;
+            for param in params {
+                let label = param.label();
+                if aborted || (param.needs_previous() && !previous_succeeded) {
+                    skipped.push(label);
+                    continue;
+                }
+                let required = param.is_required();
+                let param_response_t = param.response_type();
+                let resp = request::request(&mut client, ReqType::Popup(param), &config).await;
+                let response = Response::new(resp, param_response_t).await;
+                previous_succeeded = response.is_success();
+                if !previous_succeeded {
+                    failures.push(format!("{} ({})", label, response.status()));
+                    aborted = required;
+                }
+            }
+            let mut status = if failures.is_empty() {
+                format!("All {} requests succeeded", total - skipped.len())
+            } else {
+                format!("{} of {} requests failed: {}", failures.len(), total, failures.join(", "))
+            };
+            if !skipped.is_empty() {
+                status = format!("{}; skipped {}", status, skipped.join(", "));
+            }
+            if let Some(note) = note {
+                status = format!("{}; {}", status, note);
+            }
+            let _ = action_tx.send(Action::Status(status));
+            if failures.len() + skipped.len() < total {
+                if let Some(undo) = undo {
+                    let _ = action_tx.send(Action::Undoable(undo));
+                }
+            }
+            if let Some(refresh) = refresh {
+                let _ = action_tx.send(refresh);
+            }
+        });
+    }
+
+    // The members of the marked rows in the members and bounces views
+    fn marked_members(&self) -> Vec<members::Entry> {
+        let entries = match self.response_t {
+            Some(ResponseType::Members) => self.members.as_ref().and_then(|members| members.entries()),
+            Some(ResponseType::Bounces) => self.bounces.as_ref().and_then(|bounces| bounces.entries()),
+            _ => None,
+        }.unwrap_or_default();
+        self.ui.marked().into_iter().filter_map(|i| entries.get(i).cloned()).collect()
+    }
+
+    fn fetch_all(&self, params: Vec<PopupReqParam>) {
+        for param in params {
+            let response_t = param.response_type();
+            self.fetch(ReqType::Popup(param), response_t);
+        }
+    }
+
+    fn open_popup(&mut self, popup: Box<dyn Popup>) {
+        self.fetch_all(popup.load());
+        self.popup = Some(popup);
+    }
+
+    fn sync_selection(&mut self) {
+        if let Some(i) = self.ui.selected() {
+            if let Some(response_type) = &self.response_t {
+                match response_type {
+                    ResponseType::Domains => if let Some(domains) = &self.domains {
+                        if let Some(entries) = domains.entries() {
+                            self.config.set_domain(Some(entries[i].clone()));
+                            self.ui.set_sel_domain(Some(entries[i].mail_host()));
+                        } else {
+                            self.config.set_domain(None);
+                            self.ui.set_sel_domain(None);
+                        }
+                    }
+                    ResponseType::Lists => if let Some(lists) = &self.lists {
+                        if let Some(entries) = lists.entries() {
+                            self.config.set_list(Some(entries[i].clone()));
+                            self.ui.set_sel_list(Some(entries[i].display_name()));
+                        } else {
+                            self.config.set_list(None);
+                            self.ui.set_sel_list(None);
+                        }
+                    }
+                    ResponseType::Members => if let Some(members) = &self.members {
+                        if let Some(entries) = members.entries() {
+                            self.config.set_member(Some(entries[i].clone()));
+                        } else {
+                            self.config.set_member(None);
+                        }
+                    }
+                    ResponseType::Bounces => if let Some(bounces) = &self.bounces {
+                        if let Some(entries) = bounces.entries() {
+                            self.config.set_member(Some(entries[i].clone()));
+                        } else {
+                            self.config.set_member(None);
+                        }
+                    }
+                    ResponseType::Messages => if let Some(messages) = &self.messages {
+                        if let Some(entries) = messages.entries() {
+                            self.config.set_message(Some(entries[i].clone()));
+                        } else {
+                            self.config.set_message(None);
+                        }
+                    }
+                    ResponseType::Templates(_) => if let Some(templates) = &self.templates {
+                        if let Some(entries) = templates.entries() {
+                            self.config.set_template(Some(entries[i].clone()));
+                        } else {
+                            self.config.set_template(None);
+                        }
+                    }
+                    ResponseType::HeaderMatches => if let Some(header_matches) = &self.header_matches {
+                        if let Some(entries) = header_matches.entries() {
+                            self.config.set_header_match(Some(entries[i].clone()));
+                        } else {
+                            self.config.set_header_match(None);
+                        }
+                    }
+                    ResponseType::Queue(_) => if let Some(queue) = &self.queue {
+                        if let Some(entries) = queue.entries() {
+                            self.config.set_queue_file(Some(entries[i].clone()));
+                        } else {
+                            self.config.set_queue_file(None);
+                        }
+                    }
+                    ResponseType::Archivers |
+                    ResponseType::System(_) |
+                    ResponseType::Audit |
+                    ResponseType::Preferences(_) |
+                    ResponseType::Popup |
+                    ResponseType::PopupData(_) => {}
+                }
+            }
+        } else if let Some(response_type) = &self.response_t {
+            // Nothing matches the search, so make sure nothing hidden stays selected
+            match response_type {
+                ResponseType::Domains => {
+                    self.config.set_domain(None);
+                    self.ui.set_sel_domain(None);
+                }
+                ResponseType::Lists => {
+                    self.config.set_list(None);
+                    self.ui.set_sel_list(None);
+                }
+                ResponseType::Members |
+                ResponseType::Bounces => self.config.set_member(None),
+                ResponseType::Messages => self.config.set_message(None),
+                ResponseType::Templates(_) => self.config.set_template(None),
+                ResponseType::HeaderMatches => self.config.set_header_match(None),
+                ResponseType::Queue(_) => self.config.set_queue_file(None),
+                ResponseType::Archivers |
+                ResponseType::System(_) |
+                ResponseType::Audit => {}
+                ResponseType::Preferences(_) |
+                ResponseType::Popup |
+                ResponseType::PopupData(_) => {}
+            }
+        }
+    }
+
     fn update(&mut self, action: Action) {
         match action {
             Action::Quit => self.should_quit = true,
             Action::Domains => {
                 self.ui.set_active_menu_item(MenuItem::Domains);
-                let action_tx = self.action_tx.clone();
-                let mut client = self.client.clone();
-                let config = self.config.clone();
-                tokio::spawn(async move {
-                    let resp = request::request(&mut client, ReqType::Domains, &config).await;
-                    let response = Response::new(resp, ResponseType::Domains).await;
-                    let _ = action_tx.send(Action::RequestResponse(response));
-                });
+                self.fetch(ReqType::Domains, ResponseType::Domains);
             }
             Action::Lists => {
                 self.ui.set_active_menu_item(MenuItem::Lists);
-                let action_tx = self.action_tx.clone();
-                let mut client = self.client.clone();
-                let config = self.config.clone();
-                tokio::spawn(async move {
-                    let resp = request::request(&mut client, ReqType::Lists, &config).await;
-                    let response = Response::new(resp, ResponseType::Lists).await;
-                    let _ = action_tx.send(Action::RequestResponse(response));
-                });    
+                self.fetch(ReqType::Lists, ResponseType::Lists);
             }
             Action::Members => {
                 self.ui.set_active_menu_item(MenuItem::Members);
-                let action_tx = self.action_tx.clone();
-                let mut client = self.client.clone();
-                let config = self.config.clone();
-                tokio::spawn(async move {
-                    let resp = request::request(&mut client, ReqType::Members, &config).await;
-                    let response = Response::new(resp, ResponseType::Members).await;
-                    let _ = action_tx.send(Action::RequestResponse(response));
-                });
+                self.fetch(ReqType::Members, ResponseType::Members);
             }
             Action::Messages => {
-                if let Some(_) = &self.config.list() {
+                if self.config.list().is_some() {
                     self.ui.set_active_menu_item(MenuItem::Messages);
-                    let action_tx = self.action_tx.clone();
-                    let mut client = self.client.clone();
-                    let config = self.config.clone();
-                    tokio::spawn(async move {
-                        let resp = request::request(&mut client, ReqType::Messages, &config).await;
-                        let response = Response::new(resp, ResponseType::Messages).await;
-                        let _ = action_tx.send(Action::RequestResponse(response));
-                    });
+                    self.fetch(ReqType::Messages, ResponseType::Messages);
                 } else {
                     self.ui.set_status("Can't fetch messages: No list selected!".to_string());
                 }
             }
+            Action::System => {
+                self.ui.set_active_menu_item(MenuItem::System);
+                self.ticks = 0;
+                for part in system::PARTS {
+                    self.fetch(ReqType::System(part.to_string()), ResponseType::System(part.to_string()));
+                }
+            }
+            // Reload the dashboard every five seconds (20 ticks), so growing queues get noticed
+            Action::Tick if self.ui.active_menu_item() == MenuItem::System && self.popup.is_none() => {
+                self.ticks += 1;
+                if self.ticks >= 20 {
+                    let _ = self.action_tx.send(Action::System);
+                }
+            }
+            Action::Queue(name) => {
+                self.ui.set_active_menu_item(MenuItem::Queue);
+                self.fetch(ReqType::Queue(name.clone()), ResponseType::Queue(name));
+            }
+            // A later listing or another view may have replaced the one these were read for
+            Action::QueueHeaders(queue) if self.ui.active_menu_item() == MenuItem::Queue
+                && self.queue.as_ref().is_some_and(|q| q.same_files(&queue)) => {
+                let (header, rows) = queue.table();
+                self.queue = Some(queue);
+                self.ui.refresh_table(header, rows);
+            }
+            Action::Bounces => {
+                if self.config.list().is_some() {
+                    self.ui.set_active_menu_item(MenuItem::Bounces);
+                    self.fetch(ReqType::Members, ResponseType::Bounces);
+                } else {
+                    self.ui.set_status("Can't fetch bounces: No list selected!".to_string());
+                }
+            }
+            Action::Inject => {
+                self.open_popup(Box::new(QueueInject::new(self.config.clone())));
+            }
+            Action::Requeue => {
+                self.open_popup(Box::new(QueueInject::requeue(self.config.clone())));
+            }
+            Action::ListTemplates => {
+                if let Some(list) = self.config.list() {
+                    self.ui.set_active_menu_item(MenuItem::Templates);
+                    let scope = format!("lists/{}", list.list_id());
+                    self.fetch(ReqType::Templates(scope.clone()), ResponseType::Templates(scope));
+                } else {
+                    self.ui.set_status("Can't fetch templates: No list selected!".to_string());
+                }
+            }
+            Action::DomainTemplates => {
+                if let Some(domain) = self.config.domain() {
+                    self.ui.set_active_menu_item(MenuItem::Templates);
+                    let scope = format!("domains/{}", domain.mail_host());
+                    self.fetch(ReqType::Templates(scope.clone()), ResponseType::Templates(scope));
+                } else {
+                    self.ui.set_status("Can't fetch templates: No domain selected!".to_string());
+                }
+            }
+            Action::HeaderMatches => {
+                if self.config.list().is_some() {
+                    self.ui.set_active_menu_item(MenuItem::HeaderMatches);
+                    self.fetch(ReqType::HeaderMatches, ResponseType::HeaderMatches);
+                } else {
+                    self.ui.set_status("Can't fetch header filters: No list selected!".to_string());
+                }
+            }
+            Action::Archivers => {
+                if self.config.list().is_some() {
+                    self.ui.set_active_menu_item(MenuItem::Archivers);
+                    self.fetch(ReqType::Archivers, ResponseType::Archivers);
+                } else {
+                    self.ui.set_status("Can't fetch archivers: No list selected!".to_string());
+                }
+            }
+            Action::Aliases => {
+                if self.config.list().is_some() {
+                    self.open_popup(Box::new(AliasEdit::new(self.config.clone())));
+                } else {
+                    self.ui.set_status("Can't edit acceptable aliases: No list selected!".to_string());
+                }
+            }
+            Action::Digest => {
+                if self.config.list().is_some() {
+                    self.open_popup(Box::new(Digest::new(self.config.clone())));
+                } else {
+                    self.ui.set_status("Can't show the digest: No list selected!".to_string());
+                }
+            }
             Action::PopupSubmit => {
-                let action_tx = self.action_tx.clone();
-                let mut client = self.client.clone();
-                let config = self.config.clone();
-                let params = self.popup.as_ref().unwrap().submit();
-                let response_t = self.response_t.clone();
-                tokio::spawn(async move {
-                    let resp = request::request(&mut client, ReqType::Popup(params), &config).await;
-                    let response = Response::new(resp, ResponseType::Popup).await;
-                    let _ = action_tx.send(Action::RequestResponse(response));
-                    if let Some(response_t) = response_t {
-                        let _ = match response_t {
-                            ResponseType::Domains => action_tx.send(Action::Domains),
-                            ResponseType::Lists => action_tx.send(Action::Lists),
-                            ResponseType::Members => action_tx.send(Action::Members),
-                            ResponseType::Messages => action_tx.send(Action::Messages),
-                            ResponseType::Popup => Ok(())
-                        };
-                    }
-                });
-                self.popup = None;                
+                let popup = self.popup.as_ref().unwrap();
+                let params = popup.submit_all();
+                let undo = popup.undo();
+                if popup.batch() {
+                    let note = popup.batch_note();
+                    self.send_batch(params, note, undo);
+                } else {
+                    self.send(params, undo);
+                }
+                self.popup = None;
+            }
+            Action::Status(status) => self.ui.set_status(status),
+            Action::Undoable(undo) => self.undo.push(undo),
+            Action::Undo => match self.undo.pop() {
+                Some(Undo::Restore(description, params)) => {
+                    let note = format!("undid {}, {} more to undo", description, self.undo.len());
+                    self.send_batch(params, Some(note), None);
+                }
+                Some(undo) => self.ui.set_status(format!("Can't undo {}: Mailman keeps nothing to restore it from", undo.description())),
+                None => self.ui.set_status("Nothing to undo".to_string()),
+            },
+            Action::Mark => {
+                if self.ui.toggle_mark() {
+                    self.sync_selection();
+                } else {
+                    self.ui.set_status("Nothing to mark here".to_string());
+                }
+            }
+            // Clicking the selected row again opens it, as there's no double click in terminals
+            Action::Click(row) => {
+                if self.ui.selected_row() == Some(row) {
+                    let _ = self.action_tx.send(Action::Open);
+                } else {
+                    self.ui.select(Some(row));
+                    self.sync_selection();
+                }
+            }
+            // Left and right in the arrows preset, through the views with a tab
+            Action::PrevView |
+            Action::NextView => {
+                let views = [Action::Domains, Action::Lists, Action::Members, Action::Messages, Action::System];
+                let current = Option::<usize>::from(self.ui.active_menu_item()).unwrap_or(0).min(views.len() - 1);
+                let next = match action {
+                    Action::PrevView => (current + views.len() - 1) % views.len(),
+                    _ => (current + 1) % views.len(),
+                };
+                let _ = self.action_tx.send(views[next].clone());
             }
             Action::Down => {
                 self.ui.down();
-                if let Some(i) = self.ui.selected() {
-                    if let Some(response_type) = &self.response_t {
-                        match response_type {
-                            ResponseType::Domains => if let Some(domains) = &self.domains {
-                                if let Some(entries) = domains.entries() {
-                                    self.config.set_domain(Some(entries[i].clone()));
-                                    self.ui.set_sel_domain(Some(entries[i].mail_host()));
-                                } else {
-                                    self.config.set_domain(None);
-                                    self.ui.set_sel_domain(None);
-                                }
-                            }
-                            ResponseType::Lists => if let Some(lists) = &self.lists {
-                                if let Some(entries) = lists.entries() {
-                                    self.config.set_list(Some(entries[i].clone()));
-                                    self.ui.set_sel_list(Some(entries[i].display_name()));
-                                } else {
-                                    self.config.set_list(None);
-                                    self.ui.set_sel_list(None);
-                                }
-                            }                   
-                            ResponseType::Members => if let Some(members) = & self.members {
-                                if let Some(entries) = members.entries() {
-                                    self.config.set_member(Some(entries[i].clone()));
-                                } else {
-                                    self.config.set_member(None);
-                                }
-                            }
-                            ResponseType::Messages => if let Some(messages) = & self.messages {
-                                if let Some(entries) = messages.entries() {
-                                    self.config.set_message(Some(entries[i].clone()));
-                                } else {
-                                    self.config.set_message(None);
-                                }
-                            }    
-                            ResponseType::Popup => {}
-                        }
-                    }
-                }
+                self.sync_selection();
             }
             Action::Up => {
                 self.ui.up();
-                if let Some(i) = self.ui.selected() {
-                    if let Some(response_type) = &self.response_t {
-                        match response_type {
-                            ResponseType::Domains => if let Some(domains) = &self.domains {
-                                if let Some(entries) = domains.entries() {
-                                    self.config.set_domain(Some(entries[i].clone()));
-                                    self.ui.set_sel_domain(Some(entries[i].mail_host()));
-                                } else {
-                                    self.config.set_domain(None);
-                                    self.ui.set_sel_domain(None);
-                                }
-                            }
-                            ResponseType::Lists => if let Some(lists) = &self.lists {
-                                if let Some(entries) = lists.entries() {
-                                    self.config.set_list(Some(entries[i].clone()));
-                                    self.ui.set_sel_list(Some(entries[i].display_name()));
-                                } else {
-                                    self.config.set_list(None);
-                                    self.ui.set_sel_list(None);
-                                }
-                            }
-                            ResponseType::Members => if let Some(members) = & self.members {
-                                if let Some(entries) = members.entries() {
-                                    self.config.set_member(Some(entries[i].clone()));
-                                } else {
-                                    self.config.set_member(None);
-                                }
-                            }
-                            ResponseType::Messages => if let Some(messages) = & self.messages {
-                                if let Some(entries) = messages.entries() {
-                                    self.config.set_message(Some(entries[i].clone()));
-                                } else {
-                                    self.config.set_message(None);
-                                }
-                            }    
-                            ResponseType::Popup => {}
+                self.sync_selection();
+            }
+            Action::MoveUp |
+            Action::MoveDown => {
+                if self.response_t == Some(ResponseType::HeaderMatches) {
+                    if let (Some(list), Some(header_match)) = (self.config.list(), self.config.header_match()) {
+                        let position = header_match.position();
+                        let count = self.header_matches.as_ref().and_then(|h| h.entries()).map_or(0, |e| e.len() as u32);
+                        let new_position = match action {
+                            Action::MoveUp if position > 0 => Some(position - 1),
+                            Action::MoveDown if position + 1 < count => Some(position + 1),
+                            _ => None,
+                        };
+                        if let Some(new_position) = new_position {
+                            let url = Url::parse(&format!("{}://{}:{}/3.1/lists/{}/header-matches/{}",
+                                self.config.protocol(),
+                                self.config.host(),
+                                self.config.port(),
+                                list.list_id(),
+                                position)).unwrap();
+                            let mut map = HashMap::new();
+                            map.insert("position".to_string(), new_position.to_string());
+                            self.send(vec![PopupReqParam::new(Method::PATCH, url, map)], None);
                         }
+                    } else {
+                        self.ui.set_status("Sorry, no header filter rule to move selected".to_string());
                     }
+                } else {
+                    self.ui.set_status("Sorry, don't know how to reorder items here...".to_string());
+                }
+            }
+            Action::Search => {
+                self.ui.start_search();
+                self.sync_selection();
+            }
+            Action::NextMatch => {
+                if self.ui.filter_active() {
+                    self.ui.down();
+                    self.sync_selection();
+                } else {
+                    self.ui.set_status("No active search; type '/' to search".to_string());
+                }
+            }
+            Action::PrevMatch => {
+                if self.ui.filter_active() {
+                    self.ui.up();
+                    self.sync_selection();
+                } else {
+                    self.ui.set_status("No active search; type '/' to search".to_string());
                 }
             }
             Action::Unselect => {
@@ -446,7 +997,8 @@ impl Marge {
                             self.ui.select(None);
                             self.ui.set_sel_list(None);
                         }
-                        ResponseType::Members => {
+                        ResponseType::Members |
+                        ResponseType::Bounces => {
                             self.config.set_member(None);
                             self.ui.select(None);
                         }
@@ -454,7 +1006,24 @@ impl Marge {
                             self.config.set_message(None);
                             self.ui.select(None);
                         }
-                        ResponseType::Popup => {}
+                        ResponseType::Templates(_) => {
+                            self.config.set_template(None);
+                            self.ui.select(None);
+                        }
+                        ResponseType::HeaderMatches => {
+                            self.config.set_header_match(None);
+                            self.ui.select(None);
+                        }
+                        ResponseType::Queue(_) => {
+                            self.config.set_queue_file(None);
+                            self.ui.select(None);
+                        }
+                        ResponseType::Archivers |
+                        ResponseType::System(_) |
+                        ResponseType::Audit => self.ui.select(None),
+                        ResponseType::Preferences(_) |
+                        ResponseType::Popup |
+                        ResponseType::PopupData(_) => {}
                     }
                 }
             }
@@ -462,11 +1031,15 @@ impl Marge {
                 if let Some(response_t) = &self.response_t {
                     match response_t {
                         ResponseType::Lists => {
-                            self.popup = Some(Box::new(ListAdd::new(self.config.clone())));
+                            let domains = self.domains.as_ref().map_or(Vec::new(), |domains| domains.list_vec());
+                            self.open_popup(Box::new(ListAdd::new(self.config.clone(), domains)));
+                        }
+                        ResponseType::HeaderMatches => {
+                            self.open_popup(Box::new(HeaderMatchAdd::new(self.config.clone())));
                         }
                         ResponseType::Members => {
                             if let Some(_list) = self.config.list() {
-                                self.popup = Some(Box::new(MemberAdd::new(self.config.clone())));
+                                self.open_popup(Box::new(MemberAdd::new(self.config.clone())));
                             }
                             else {
                                 self.ui.set_status("You must select a list before I can add members.".to_string());
@@ -480,23 +1053,58 @@ impl Marge {
                     self.ui.set_status("Sorry, nothing to add here".to_string());
                 }
             }
+            Action::SortBy(column) => {
+                if self.ui.sort_by(column) {
+                    self.sync_selection();
+                } else {
+                    self.ui.set_status(format!("Can't sort here by column {}", column + 1));
+                }
+            }
+            Action::Find => {
+                self.open_popup(Box::new(MemberFind::new(self.config.clone())));
+            }
             Action::Delete => {
                 if let Some(response_t) = &self.response_t {
                     match response_t {
                         ResponseType::Lists => {
                             if let Some(_list) = self.config.list() {
-                                self.popup = Some(Box::new(ListDel::new(self.config.clone())));
+                                self.open_popup(Box::new(ListDel::new(self.config.clone())));
                             } else {
                                 self.ui.set_status("Sorry, no list to delete selected".to_string());
                             }
                         }
+                        ResponseType::Members if !self.ui.marked().is_empty() => {
+                            let _ = self.action_tx.send(Action::MassDelete);
+                        }
                         ResponseType::Members => {
                             if let Some(_member) = self.config.member() {
-                                self.popup = Some(Box::new(MemberDel::new(self.config.clone())));
+                                self.open_popup(Box::new(MemberDel::new(self.config.clone())));
                             } else {
                                 self.ui.set_status("Sorry, no member to delete selected".to_string());
                             }
                         }
+                        ResponseType::Queue(_) => {
+                            if let Some(_queue_file) = self.config.queue_file() {
+                                self.open_popup(Box::new(QueueFile::new(self.config.clone())));
+                            } else {
+                                self.ui.set_status("Sorry, no queue file to delete selected".to_string());
+                            }
+                        }
+                        ResponseType::HeaderMatches => {
+                            if let Some(_header_match) = self.config.header_match() {
+                                let count = self.header_matches.as_ref().and_then(|h| h.entries()).map_or(0, |e| e.len() as u32);
+                                self.open_popup(Box::new(HeaderMatchDel::new(self.config.clone(), count)));
+                            } else {
+                                self.ui.set_status("Sorry, no header filter rule to delete selected".to_string());
+                            }
+                        }
+                        ResponseType::Templates(_) => {
+                            if let Some(_template) = self.config.template() {
+                                self.open_popup(Box::new(TemplateReset::new(self.config.clone())));
+                            } else {
+                                self.ui.set_status("Sorry, no template to reset selected".to_string());
+                            }
+                        }
                         _ => {
                             self.ui.set_status("Sorry, don't know how to delete items hier...".to_string());
                         }
@@ -505,16 +1113,162 @@ impl Marge {
                     self.ui.set_status("Sorry, nothing to delete here".to_string());
                 }
             }
+            Action::MassDelete => {
+                if self.config.list().is_some() {
+                    let marked = self.marked_members().iter().map(|member| member.email()).collect();
+                    self.open_popup(Box::new(MemberMassDel::new(self.config.clone(), marked)));
+                } else {
+                    self.ui.set_status("You must select a list before I can unsubscribe members.".to_string());
+                }
+            }
+            Action::Sync => {
+                if self.config.list().is_some() {
+                    self.open_popup(Box::new(MemberSync::new(self.config.clone())));
+                } else {
+                    self.ui.set_status("You must select a list before I can sync its members.".to_string());
+                }
+            }
+            // On a list, copying means taking over another list's settings
+            Action::Copy if self.response_t == Some(ResponseType::Lists) => {
+                let _ = self.action_tx.send(Action::CloneConfig);
+            }
+            Action::CloneConfig => {
+                if self.config.list().is_none() {
+                    self.ui.set_status("Select the list to clone the settings to first".to_string());
+                } else if let Some(lists) = self.lists.as_ref().and_then(|lists| lists.entries()) {
+                    self.open_popup(Box::new(ConfigClone::new(self.config.clone(), lists)));
+                } else {
+                    self.ui.set_status("Open the lists view first, so I know where to clone from".to_string());
+                }
+            }
+            Action::Copy => {
+                let mut members = self.marked_members();
+                if members.is_empty() && matches!(self.response_t, Some(ResponseType::Members | ResponseType::Bounces)) {
+                    members.extend(self.config.member());
+                }
+                if members.is_empty() {
+                    self.ui.set_status("Select or mark (Space) the members to copy in the members view".to_string());
+                } else if let Some(lists) = self.lists.as_ref().and_then(|lists| lists.entries()) {
+                    self.open_popup(Box::new(MemberCopy::new(self.config.clone(), members, lists)));
+                } else {
+                    self.ui.set_status("Open the lists view first, so I know where to copy to".to_string());
+                }
+            }
+            // The audit log is local, so it's shown right away instead of waiting for a response
+            Action::Audit => {
+                self.ui.set_active_menu_item(MenuItem::Audit);
+                self.response_t = Some(ResponseType::Audit);
+                match Audit::read() {
+                    Ok(audit) => {
+                        let (header, rows) = audit.table();
+                        self.ui.set_table(header, rows);
+                        let path = audit::path().map_or(String::new(), |path| path.display().to_string());
+                        if audit.entries().is_none() {
+                            self.ui.set_list_vec(vec![format!("Nothing logged in {} yet", path)]);
+                        }
+                        self.ui.set_status(format!("Audit log: {}", path));
+                        self.audit = Some(audit);
+                    }
+                    Err(e) => {
+                        self.audit = None;
+                        self.ui.set_list_vec(vec![format!("Error: {}", e)]);
+                    }
+                }
+            }
+            Action::BackupRestore => {
+                self.open_popup(Box::new(ListBackup::new(self.config.clone())));
+            }
+            Action::Backup(path) => {
+                let Some(list) = self.config.list() else {
+                    self.ui.set_status("Can't back up: No list selected!".to_string());
+                    return;
+                };
+                self.ui.set_status(format!("Backing up {}...", list.fqdn_listname()));
+                let action_tx = self.action_tx.clone();
+                let mut client = self.client.clone();
+                let config = self.config.clone();
+                tokio::spawn(async move {
+                    let result = match backup::Bundle::new(&mut client, &config, &list.list_id()).await {
+                        Ok(bundle) => bundle.save(&path).map(|_| bundle),
+                        Err(e) => Err(e),
+                    };
+                    let status = match result {
+                        Ok(bundle) => format!("Saved {} to {}", bundle.summary().join(", "), path.display()),
+                        Err(e) => format!("Backup failed: {}", e),
+                    };
+                    let _ = action_tx.send(Action::Status(status));
+                });
+            }
             Action::Open => {
                 if let Some(response_t) = &self.response_t {
                     match response_t {
+                        ResponseType::Lists => {
+                            if let Some(_list) = self.config.list() {
+                                self.open_popup(Box::new(ListDetail::new(self.config.clone())));
+                            } else {
+                                self.ui.set_status("Sorry, no list to open selected".to_string());
+                            }
+                        }
+                        ResponseType::System(_) => {
+                            if let Some(name) = self.ui.selected().and_then(|i| self.system.queue(i)) {
+                                let _ = self.action_tx.send(Action::Queue(name));
+                            } else {
+                                self.ui.set_status("Select a queue to see its files".to_string());
+                            }
+                        }
+                        ResponseType::Queue(_) => {
+                            if let Some(_queue_file) = self.config.queue_file() {
+                                self.open_popup(Box::new(QueueFile::new(self.config.clone())));
+                            } else {
+                                self.ui.set_status("Sorry, no queue file to open selected".to_string());
+                            }
+                        }
+                        ResponseType::Archivers => {
+                            let archiver = self.ui.selected()
+                                .and_then(|i| self.archivers.as_ref().and_then(|a| a.entries()).and_then(|e| e.get(i).cloned()));
+                            if let (Some(list), Some(archiver)) = (self.config.list(), archiver) {
+                                let url = Url::parse(&format!("{}://{}:{}/3.1/lists/{}/archivers",
+                                    self.config.protocol(),
+                                    self.config.host(),
+                                    self.config.port(),
+                                    list.list_id())).unwrap();
+                                let mut map = HashMap::new();
+                                map.insert(archiver.name(), (!archiver.enabled()).to_string());
+                                self.send(vec![PopupReqParam::new(Method::PATCH, url, map)], None);
+                            } else {
+                                self.ui.set_status("Sorry, no archiver to toggle selected".to_string());
+                            }
+                        }
                         ResponseType::Messages => {
                             if let Some(_message) = self.config.message() {
-                                self.popup = Some(Box::new(MessageMod::new(self.config.clone())));
+                                self.open_popup(Box::new(MessageMod::new(self.config.clone())));
                             } else {
                                 self.ui.set_status("Sorry, no item to open selected".to_string());
                             }
                         }
+                        ResponseType::Audit => {
+                            let entry = self.ui.selected()
+                                .and_then(|i| self.audit.as_ref().and_then(|a| a.entries()).and_then(|e| e.get(i).cloned()));
+                            if let Some(entry) = entry {
+                                self.open_popup(Box::new(AuditDetail::new(entry)));
+                            } else {
+                                self.ui.set_status("Sorry, no audit log entry to open selected".to_string());
+                            }
+                        }
+                        ResponseType::Bounces => {
+                            if let Some(_member) = self.config.member() {
+                                self.open_popup(Box::new(BounceMod::new(self.config.clone())));
+                            } else {
+                                self.ui.set_status("Sorry, no member to open selected".to_string());
+                            }
+                        }
+                        ResponseType::Templates(_) => {
+                            if let Some(_template) = self.config.template() {
+                                self.open_popup(Box::new(TemplateEdit::new(self.config.clone())));
+                            } else {
+                                self.ui.set_status("Sorry, no template to edit selected".to_string());
+                            }
+                        }
                         _ => {
                             self.ui.set_status("Sorry, don't know how to open items here...".to_string());
                         }
@@ -524,15 +1278,87 @@ impl Marge {
                 }
             }
             Action::RequestResponse(response) => {
+                if let ResponseType::PopupData(_) = response.response_type() {
+                    if let Some(popup) = &mut self.popup {
+                        popup.loaded(response);
+                    }
+                    return;
+                }
+                // Delivery statuses only fill in a column of the bounce view
+                if let ResponseType::Preferences(member_id) = response.response_type() {
+                    if let Some(bounces) = &mut self.bounces {
+                        if let Some(delivery_status) = bounce_mod::delivery_status(&response.text()).filter(|_| response.is_success()) {
+                            bounces.set_delivery_status(&member_id, delivery_status);
+                            if self.response_t == Some(ResponseType::Bounces) {
+                                let (header, rows) = bounces.table();
+                                self.ui.refresh_table(header, rows);
+                            }
+                        }
+                    }
+                    return;
+                }
+                // The system view is filled by several requests, late ones mustn't overwrite another view
+                if let ResponseType::System(_) = response.response_type() {
+                    if self.ui.active_menu_item() != MenuItem::System {
+                        return;
+                    }
+                }
                 // Don't override status bar status, if coming from a popup
                 if let Some(response_t) = &self.response_t {
-                    if *response_t != ResponseType::Popup {
+                    if *response_t != ResponseType::Popup || response.response_type() == ResponseType::Popup {
                         self.ui.set_status(response.status());
                     }
                 } else { // self.response_t is None; make sure, status bar will be set when marge has just started
                     self.ui.set_status(response.status());
                 }
                 self.response_t = Some(response.response_type());
+                if !response.is_success() && response.response_type() != ResponseType::Popup {
+                    match response.response_type() {
+                        ResponseType::Domains => {
+                            self.domains = None;
+                            self.config.set_domain(None);
+                            self.ui.set_sel_domain(None);
+                        }
+                        ResponseType::Lists => {
+                            self.lists = None;
+                            self.config.set_list(None);
+                            self.ui.set_sel_list(None);
+                        }
+                        ResponseType::Members => {
+                            self.members = None;
+                            self.config.set_member(None);
+                        }
+                        ResponseType::Bounces => {
+                            self.bounces = None;
+                            self.config.set_member(None);
+                        }
+                        ResponseType::Messages => {
+                            self.messages = None;
+                            self.config.set_message(None);
+                        }
+                        ResponseType::Templates(_) => {
+                            self.templates = None;
+                            self.config.set_template(None);
+                        }
+                        ResponseType::HeaderMatches => {
+                            self.header_matches = None;
+                            self.config.set_header_match(None);
+                        }
+                        ResponseType::Archivers => self.archivers = None,
+                        ResponseType::System(_) => {}
+                        ResponseType::Queue(_) => {
+                            self.queue = None;
+                            self.config.set_queue_file(None);
+                        }
+                        // Never comes from the server
+                        ResponseType::Audit |
+                        ResponseType::Preferences(_) |
+                        ResponseType::Popup |
+                        ResponseType::PopupData(_) => {}
+                    }
+                    self.ui.set_list_vec(vec![format!("Error: {}", response.text())]);
+                    return;
+                }
                 match response.response_type() {
                     ResponseType::Domains => {
                         let domains: Result<Domains, serde_json::Error> = serde_json::from_str(&response.text());
@@ -559,7 +1385,8 @@ impl Marge {
                         match lists {
                             Ok(lists) => {
                                 self.lists = Some(lists.clone());
-                                self.ui.set_list_vec(lists.clone().list_vec());
+                                let (header, rows) = lists.table(self.config.list_columns());
+                                self.ui.set_table(header, rows);
                                 if let Some(entries) = lists.entries() {
                                     self.config.set_list(Some(entries[0].clone()));
                                     self.ui.set_sel_list(Some(entries[0].display_name()));
@@ -579,7 +1406,8 @@ impl Marge {
                         match members {
                             Ok(members) => {
                                 self.members = Some(members.clone());
-                                self.ui.set_list_vec(members.clone().list_vec());
+                                let (header, rows) = members.table(self.config.member_columns());
+                                self.ui.set_table(header, rows);
                                 if let Some(entries) = members.entries() {
                                     self.config.set_member(Some(entries[0].clone()));
                                 } else {
@@ -616,10 +1444,118 @@ impl Marge {
                             }
                         }                        
                     },
-                    ResponseType::Popup => {
+                    ResponseType::Templates(scope) => {
+                        match Templates::new(&scope, &response.text()) {
+                            Ok(templates) => {
+                                self.templates = Some(templates.clone());
+                                let (header, rows) = templates.table();
+                                self.ui.set_table(header, rows);
+                                if let Some(entries) = templates.entries() {
+                                    self.config.set_template(Some(entries[0].clone()));
+                                } else {
+                                    self.config.set_template(None);
+                                }
+                            }
+                            Err(e) => {
+                                self.templates = None;
+                                self.ui.set_list_vec(vec![format!("Error: {}", e)]);
+                            }
+                        }
+                    }
+                    ResponseType::HeaderMatches => {
+                        let header_matches: Result<HeaderMatches, serde_json::Error> = serde_json::from_str(&response.text());
+                        match header_matches {
+                            Ok(header_matches) => {
+                                self.header_matches = Some(header_matches.clone());
+                                let (header, rows) = header_matches.table();
+                                self.ui.set_table(header, rows);
+                                if let Some(entries) = header_matches.entries() {
+                                    self.config.set_header_match(Some(entries[0].clone()));
+                                } else {
+                                    self.config.set_header_match(None);
+                                }
+                            }
+                            Err(e) => {
+                                self.header_matches = None;
+                                self.ui.set_list_vec(vec![format!("Error: {}", e)]);
+                            }
+                        }
+                    }
+                    ResponseType::Archivers => {
+                        match Archivers::new(&response.text()) {
+                            Ok(archivers) => {
+                                self.archivers = Some(archivers.clone());
+                                let (header, rows) = archivers.table();
+                                self.ui.set_table(header, rows);
+                            }
+                            Err(e) => {
+                                self.archivers = None;
+                                self.ui.set_list_vec(vec![format!("Error: {}", e)]);
+                            }
+                        }
+                    }
+                    ResponseType::System(part) => {
+                        match self.system.update(&part, &response.text()) {
+                            Ok(()) => {
+                                let (header, rows) = self.system.table();
+                                self.ui.refresh_table(header, rows);
+                            }
+                            Err(e) => self.ui.set_list_vec(vec![format!("Error: {}", e)]),
+                        }
+                    }
+                    ResponseType::Queue(_) => {
+                        let queue: Result<Queue, serde_json::Error> = serde_json::from_str(&response.text());
+                        match queue {
+                            Ok(queue) => {
+                                self.queue = Some(queue.clone());
+                                let (header, rows) = queue.table();
+                                self.ui.set_table(header, rows);
+                                self.read_queue_headers(queue.clone());
+                                if let Some(entries) = queue.entries() {
+                                    self.config.set_queue_file(Some(entries[0].clone()));
+                                } else {
+                                    self.config.set_queue_file(None);
+                                    self.ui.set_list_vec(vec!["Queue is empty".to_string()]);
+                                }
+                            }
+                            Err(e) => {
+                                self.queue = None;
+                                self.ui.set_list_vec(vec![format!("Error: {}", e)]);
+                            }
+                        }
+                    }
+                    ResponseType::Bounces => {
+                        match Bounces::new(&response.text()) {
+                            Ok(bounces) => {
+                                let (header, rows) = bounces.table();
+                                self.ui.set_table(header, rows);
+                                if let Some(entries) = bounces.entries() {
+                                    self.config.set_member(Some(entries[0].clone()));
+                                } else {
+                                    self.config.set_member(None);
+                                }
+                                for member_id in bounces.with_history() {
+                                    self.fetch(ReqType::Preferences(member_id.clone()), ResponseType::Preferences(member_id));
+                                }
+                                self.bounces = Some(bounces);
+                            }
+                            Err(e) => {
+                                self.bounces = None;
+                                self.ui.set_list_vec(vec![format!("Error: {}", e)]);
+                            }
+                        }
+                    }
+                    ResponseType::Audit |
+                    ResponseType::Preferences(_) |
+                    ResponseType::Popup |
+                    ResponseType::PopupData(_) => {
                         // nothing to do here...
                     }
                 }
+                // A search filter survives reloading the same view, so select what is actually shown
+                if self.ui.filter_active() {
+                    self.sync_selection();
+                }
             }
             _ => {}
         }
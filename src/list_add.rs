@@ -2,65 +2,329 @@ use std::collections::HashMap;
 
 use ratatui::{prelude::*, widgets::*};
 use reqwest::{Method, Url};
+use serde_json::value::Value;
 use tui_textarea::{TextArea, Input, Key};
 
-use crate::{config::Config, popup::{Popup, PopupStatus, PopupReqParam}};
+use crate::{config::Config, popup::{self, Popup, PopupStatus, PopupReqParam}, response::{Response, ResponseType}};
+
+#[derive(Clone, Copy, PartialEq)]
+enum Field {
+    Domain,
+    Name,
+    Style,
+    DisplayName,
+    Description,
+    Owner,
+    Advertised,
+}
+
+const FIELDS: [Field; 7] = [
+    Field::Domain,
+    Field::Name,
+    Field::Style,
+    Field::DisplayName,
+    Field::Description,
+    Field::Owner,
+    Field::Advertised,
+];
 
 #[derive(Clone)]
 pub struct ListAdd<'a> {
     config: Config,
-    text_area: TextArea<'a>,
+    domains: Vec<String>,
+    domain: usize,
+    styles: Vec<(String, String)>,
+    style: usize,
+    name: TextArea<'a>,
+    display_name: TextArea<'a>,
+    description: TextArea<'a>,
+    owner: TextArea<'a>,
+    advertised: bool,
+    focus: usize,
+    error: Option<String>,
 }
 
 impl<'a> ListAdd<'a> {
-    pub fn new(config: Config) -> Self {
+    pub fn new(config: Config, domains: Vec<String>) -> Self {
+        let domain = config.domain()
+            .and_then(|selected| domains.iter().position(|d| *d == selected.mail_host()))
+            .unwrap_or(0);
         let mut text_area = TextArea::default();
-        text_area.set_block(Block::default()
-            .borders(Borders::ALL)
-            .border_type(BorderType::Rounded)
-            .title(" Add List ".to_string())
-            .style(Style::default().fg(Color::Blue)),
-        );
+        text_area.set_cursor_line_style(Style::default());
 
-        Self {
+        let mut list_add = Self {
             config,
-            text_area,
+            domains,
+            domain,
+            styles: Vec::new(),
+            style: 0,
+            name: text_area.clone(),
+            display_name: text_area.clone(),
+            description: text_area.clone(),
+            owner: text_area,
+            advertised: true,
+            focus: 0,
+            error: None,
+        };
+        list_add.set_focus(1);
+        list_add
+    }
+
+    fn set_focus(&mut self, focus: usize) {
+        self.focus = focus;
+        for (i, field) in FIELDS.iter().enumerate() {
+            if let Some(text_area) = self.text_area_mut(*field) {
+                if i == focus {
+                    text_area.set_cursor_style(Style::default().add_modifier(Modifier::REVERSED));
+                } else {
+                    text_area.set_cursor_style(Style::default());
+                }
+            }
+        }
+    }
+
+    fn text_area(&self, field: Field) -> Option<&TextArea<'a>> {
+        match field {
+            Field::Name => Some(&self.name),
+            Field::DisplayName => Some(&self.display_name),
+            Field::Description => Some(&self.description),
+            Field::Owner => Some(&self.owner),
+            _ => None,
+        }
+    }
+
+    fn text_area_mut(&mut self, field: Field) -> Option<&mut TextArea<'a>> {
+        match field {
+            Field::Name => Some(&mut self.name),
+            Field::DisplayName => Some(&mut self.display_name),
+            Field::Description => Some(&mut self.description),
+            Field::Owner => Some(&mut self.owner),
+            _ => None,
+        }
+    }
+
+    fn value(&self, field: Field) -> String {
+        self.text_area(field).map_or(String::new(), |text_area| text_area.lines()[0].trim().to_string())
+    }
+
+    fn label(field: Field) -> &'static str {
+        match field {
+            Field::Domain => "Domain",
+            Field::Name => "List name",
+            Field::Style => "Style",
+            Field::DisplayName => "Display name",
+            Field::Description => "Description",
+            Field::Owner => "Owner address",
+            Field::Advertised => "Advertised",
+        }
+    }
+
+    // Cycles through the choices of the selector fields
+    fn change(&mut self, field: Field, forward: bool) {
+        let step = |i: usize, len: usize| {
+            if len == 0 {
+                0
+            } else if forward {
+                (i + 1) % len
+            } else {
+                (i + len - 1) % len
+            }
+        };
+        match field {
+            Field::Domain => self.domain = step(self.domain, self.domains.len()),
+            Field::Style => self.style = step(self.style, self.styles.len()),
+            Field::Advertised => self.advertised = !self.advertised,
+            _ => {}
+        }
+    }
+
+    fn fqdn_listname(&self) -> String {
+        format!("{}@{}", self.value(Field::Name), self.domains.get(self.domain).cloned().unwrap_or_default())
+    }
+
+    // Creating the list; its settings and owner follow in submit_all()
+    fn create(&self) -> PopupReqParam {
+        let url = Url::parse(&format!("{}://{}:{}/3.1/lists",
+            self.config.protocol(),
+            self.config.host(),
+            self.config.port())).unwrap();
+
+        let mut map = HashMap::new();
+        map.insert("fqdn_listname".to_string(), self.fqdn_listname());
+        if let Some((style, _)) = self.styles.get(self.style) {
+            map.insert("style_name".to_string(), style.clone());
+        }
+
+        PopupReqParam::new(Method::POST, url, map)
+    }
+
+    fn list_id(&self) -> String {
+        format!("{}.{}", self.value(Field::Name), self.domains.get(self.domain).cloned().unwrap_or_default())
+    }
+
+    fn validate(&self) -> Option<String> {
+        let name = self.value(Field::Name);
+        let owner = self.value(Field::Owner);
+        if self.domains.is_empty() {
+            Some("No domains loaded; open the Domains view first".to_string())
+        } else if name.is_empty() {
+            Some("The list name must not be empty".to_string())
+        } else if !name.chars().all(|c| c.is_ascii_alphanumeric() || "._+-".contains(c)) {
+            Some("The list name may only contain letters, digits and . _ + -".to_string())
+        } else if !owner.is_empty() && !owner.contains('@') {
+            Some("The owner address is not a valid email address".to_string())
+        } else {
+            None
         }
     }
 }
 
 impl Popup for ListAdd<'_> {
     fn render(&mut self, frame: &mut Frame) {
-        let area = Rect {
-            width: 80,
-            height: 3,
-            x: 42,
-            y: 20,
-        };
+        let area = popup::area(frame, 80, 12);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(" Add List ".to_string())
+            .style(Style::default().fg(Color::Blue));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        for (i, field) in FIELDS.iter().enumerate() {
+            let label_area = Rect { x: inner.x, y: inner.y + i as u16, width: 16, height: 1 }.intersection(inner);
+            let value_area = Rect { x: inner.x + 16, y: inner.y + i as u16, width: inner.width.saturating_sub(16), height: 1 }.intersection(inner);
+            let mut label_style = Style::default().fg(Color::LightRed);
+            if i == self.focus {
+                label_style = label_style.add_modifier(Modifier::BOLD | Modifier::REVERSED);
+            }
+            frame.render_widget(Paragraph::new(Self::label(*field)).style(label_style), label_area);
+
+            if let Some(text_area) = self.text_area(*field) {
+                frame.render_widget(text_area, value_area);
+            } else {
+                let value = match field {
+                    Field::Domain => format!("< {} >", self.domains.get(self.domain).map_or("(none loaded)", |d| d.as_str())),
+                    Field::Style => match self.styles.get(self.style) {
+                        Some((name, description)) => format!("< {} > {}", name, description),
+                        None => "< server default >".to_string(),
+                    },
+                    _ => format!("[{}]", if self.advertised { "x" } else { " " }),
+                };
+                frame.render_widget(Paragraph::new(value), value_area);
+            }
+        }
 
-        frame.render_widget(&self.text_area, area);
+        let hint = match &self.error {
+            Some(error) => Line::styled(error.clone(), Style::default().fg(Color::Red)),
+            None => Line::raw("Tab/Up/Down: field, Left/Right/Space: choice, Enter: create, Esc: cancel"),
+        };
+        let hint_area = Rect { x: inner.x, y: inner.y + FIELDS.len() as u16 + 1, width: inner.width, height: 1 }.intersection(inner);
+        frame.render_widget(Paragraph::new(hint), hint_area);
     }
 
     fn input(&mut self, input: Input) -> PopupStatus {
         let mut status = PopupStatus::Continue;
+        let field = FIELDS[self.focus];
         match input {
             Input { key: Key::Esc, .. } => status = PopupStatus::Cancel,
-            Input { key: Key::Enter, .. } => status = PopupStatus::Submit,
-            input => { self.text_area.input(input); }
+            Input { key: Key::Enter, .. } => {
+                self.error = self.validate();
+                if self.error.is_none() {
+                    status = PopupStatus::Submit;
+                }
+            }
+            Input { key: Key::Tab, .. } |
+            Input { key: Key::Down, .. } => self.set_focus((self.focus + 1) % FIELDS.len()),
+            Input { key: Key::Up, .. } => self.set_focus((self.focus + FIELDS.len() - 1) % FIELDS.len()),
+            input => {
+                if let Some(text_area) = self.text_area_mut(field) {
+                    text_area.input(input);
+                } else {
+                    match input {
+                        Input { key: Key::Left, .. } => self.change(field, false),
+                        Input { key: Key::Right, .. } |
+                        Input { key: Key::Char(' '), .. } => self.change(field, true),
+                        _input => {}
+                    }
+                }
+            }
         }
 
         status
     }
 
-    fn submit(&self) -> PopupReqParam {
-        let url = Url::parse(&format!("{}://{}:{}/3.1/lists",
+    fn paste(&mut self, text: &str) {
+        if let Some(text_area) = self.text_area_mut(FIELDS[self.focus]) {
+            popup::paste_line(text_area, text);
+        }
+    }
+
+    fn submit_all(&self) -> Vec<PopupReqParam> {
+        let mut params = vec![self.create()];
+
+        let url = Url::parse(&format!("{}://{}:{}/3.1/lists/{}/config",
+            self.config.protocol(),
+            self.config.host(),
+            self.config.port(),
+            self.fqdn_listname())).unwrap();
+        let mut map = HashMap::new();
+        for (key, field) in [("display_name", Field::DisplayName), ("description", Field::Description)] {
+            let value = self.value(field);
+            if !value.is_empty() {
+                map.insert(key.to_string(), value);
+            }
+        }
+        map.insert("advertised".to_string(), self.advertised.to_string());
+        params.push(PopupReqParam::new(Method::PATCH, url, map));
+
+        let owner = self.value(Field::Owner);
+        if !owner.is_empty() {
+            let url = Url::parse(&format!("{}://{}:{}/3.1/members",
+                self.config.protocol(),
+                self.config.host(),
+                self.config.port())).unwrap();
+            let mut map = HashMap::new();
+            map.insert("list_id".to_string(), self.list_id());
+            map.insert("subscriber".to_string(), owner);
+            map.insert("role".to_string(), "owner".to_string());
+            map.insert("pre_verified".to_string(), "true".to_string());
+            map.insert("pre_confirmed".to_string(), "true".to_string());
+            map.insert("pre_approved".to_string(), "true".to_string());
+            params.push(PopupReqParam::new(Method::POST, url, map));
+        }
+
+        params
+    }
+
+    fn load(&self) -> Vec<PopupReqParam> {
+        let url = Url::parse(&format!("{}://{}:{}/3.1/lists/styles",
             self.config.protocol(),
             self.config.host(),
             self.config.port())).unwrap();
 
-        let mut map = HashMap::new();
-        map.insert("fqdn_listname".to_string(), self.text_area.lines()[0].clone());
+        vec![PopupReqParam::new_for(Method::GET, url, HashMap::new(), ResponseType::PopupData("styles".to_string()))]
+    }
 
-        PopupReqParam::new(Method::POST, url, map)
+    fn loaded(&mut self, response: Response) {
+        if !response.is_success() {
+            self.error = Some(format!("Can't load list styles: {}", response.status()));
+            return;
+        }
+        if let Ok(value) = serde_json::from_str::<Value>(&response.text()) {
+            // Newer Mailman versions describe each style, older ones only list their names
+            if let Some(styles) = value.get("styles").and_then(Value::as_array) {
+                self.styles = styles.iter().filter_map(|style| {
+                    let name = style.get("name")?.as_str()?.to_string();
+                    let description = style.get("description").and_then(Value::as_str).unwrap_or_default().to_string();
+                    Some((name, description))
+                }).collect();
+            } else if let Some(names) = value.get("style_names").and_then(Value::as_array) {
+                self.styles = names.iter().filter_map(|name| Some((name.as_str()?.to_string(), String::new()))).collect();
+            }
+            if let Some(default) = value.get("default").and_then(Value::as_str) {
+                self.style = self.styles.iter().position(|(name, _)| name == default).unwrap_or(0);
+            }
+        }
     }
-}
\ No newline at end of file
+}
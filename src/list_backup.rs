@@ -0,0 +1,124 @@
+use std::path::PathBuf;
+
+use ratatui::{prelude::*, widgets::*};
+use tui_textarea::{TextArea, Input, Key};
+
+use crate::{backup::Bundle, config::Config, popup::{self, Popup, PopupReqParam, PopupStatus}, Action};
+
+#[derive(Clone)]
+pub struct ListBackup<'a> {
+    config: Config,
+    file: TextArea<'a>,
+    // The bundle to restore and the name to restore it as, once it was read
+    restore: Option<(Bundle, TextArea<'a>)>,
+    error: Option<String>,
+}
+
+impl<'a> ListBackup<'a> {
+    pub fn new(config: Config) -> Self {
+        let file = config.list().map_or(String::new(), |list| format!("{}.json", list.list_id()));
+        let mut file = TextArea::new(vec![file]);
+        file.move_cursor(tui_textarea::CursorMove::End);
+        file.set_cursor_line_style(Style::default());
+        file.set_block(Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(" Backup file ".to_string())
+            .style(Style::default().fg(Color::Blue)),
+        );
+
+        Self {
+            config,
+            file,
+            restore: None,
+            error: None,
+        }
+    }
+
+    fn path(&self) -> PathBuf {
+        PathBuf::from(self.file.lines()[0].trim())
+    }
+
+    fn read(&mut self) {
+        match Bundle::load(&self.path()) {
+            Ok(bundle) => {
+                let mut name = TextArea::new(vec![bundle.fqdn_listname()]);
+                name.move_cursor(tui_textarea::CursorMove::End);
+                name.set_cursor_line_style(Style::default());
+                name.set_block(Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title(format!(" Restore as (on {}) ", self.config.host()))
+                    .style(Style::default().fg(Color::Blue)),
+                );
+                self.restore = Some((bundle, name));
+                self.error = None;
+            }
+            Err(e) => self.error = Some(e.to_string()),
+        }
+    }
+}
+
+impl Popup for ListBackup<'_> {
+    fn render(&mut self, frame: &mut Frame) {
+        // The field and what's below it
+        let below = self.restore.as_ref().map_or(1, |(bundle, _)| bundle.summary().len() as u16 + 2);
+        let area = popup::area(frame, 80, 3 + below);
+        let chunks = Layout::vertical([Constraint::Length(3), Constraint::Min(0)]).split(area);
+
+        if let Some((bundle, name)) = &self.restore {
+            frame.render_widget(name, chunks[0]);
+            let mut text: Vec<Line> = bundle.summary().into_iter().map(Line::raw).collect();
+            text.push(Line::from(""));
+            text.push(Line::raw("Enter: restore, Esc: back"));
+            frame.render_widget(Paragraph::new(text).fg(Color::LightRed), chunks[1]);
+            return;
+        }
+
+        frame.render_widget(&self.file, chunks[0]);
+        let hint = match &self.error {
+            Some(error) => Line::styled(error.clone(), Style::default().fg(Color::Red)),
+            None if self.config.list().is_some() => Line::raw("Ctrl-B: back up the selected list, Ctrl-R: restore, Esc: cancel"),
+            None => Line::raw("Ctrl-R: restore a list, Esc: cancel (select a list to back it up)"),
+        };
+        frame.render_widget(Paragraph::new(hint).fg(Color::LightRed), chunks[1]);
+    }
+
+    fn input(&mut self, input: Input) -> PopupStatus {
+        let mut status = PopupStatus::Continue;
+        if let Some((_, name)) = &mut self.restore {
+            match input {
+                Input { key: Key::Esc, .. } => self.restore = None,
+                Input { key: Key::Enter, .. } => status = PopupStatus::Submit,
+                input => { name.input(input); }
+            }
+            return status;
+        }
+        match input {
+            Input { key: Key::Esc, .. } => status = PopupStatus::Cancel,
+            Input { key: Key::Char('b'), ctrl: true, .. } if self.config.list().is_some() => {
+                status = PopupStatus::Action(Action::Backup(self.path()));
+            }
+            Input { key: Key::Char('r'), ctrl: true, .. } => self.read(),
+            Input { key: Key::Enter, .. } => {}
+            input => { self.file.input(input); }
+        }
+
+        status
+    }
+
+    fn paste(&mut self, text: &str) {
+        match &mut self.restore {
+            Some((_, name)) => popup::paste_line(name, text),
+            None => popup::paste_line(&mut self.file, text),
+        }
+    }
+
+    fn submit_all(&self) -> Vec<PopupReqParam> {
+        self.restore.as_ref().map_or(Vec::new(), |(bundle, name)| bundle.requests(&self.config, name.lines()[0].trim()))
+    }
+
+    fn batch(&self) -> bool {
+        true
+    }
+}
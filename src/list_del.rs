@@ -4,12 +4,13 @@ use ratatui::{prelude::*, widgets::*};
 use reqwest::{Method, Url};
 use tui_textarea::{Input, Key};
 
-use crate::{config::Config, popup::{Popup, PopupReqParam, PopupStatus}};
+use crate::{config::Config, popup::{self, Buttons, Popup, PopupReqParam, PopupStatus}, undo::Undo};
 
 #[derive(Clone)]
 pub struct ListDel<'a> {
     config: Config,
     paragraph: Paragraph<'a>,
+    buttons: Buttons,
 }
 
 impl<'a> ListDel<'a> {
@@ -26,20 +27,17 @@ impl<'a> ListDel<'a> {
         Self {
             config,
             paragraph,
+            buttons: Buttons::new(&[("Yes", Key::Enter), ("No", Key::Esc)]),
         }
     }
 }
 
 impl Popup for ListDel<'_> {
     fn render(&mut self, frame: &mut Frame) {
-        let area = Rect {
-            width: 62,
-            height: 3,
-            x: 42,
-            y: 20,
-        };
+        let area = popup::area(frame, 62, 4);
 
         frame.render_widget(self.paragraph.clone(), area);
+        self.buttons.render(frame, area);
     }
 
     fn input(&mut self, input: Input) -> PopupStatus {
@@ -57,7 +55,11 @@ impl Popup for ListDel<'_> {
         status
     }
 
-    fn submit(&self) -> PopupReqParam {
+    fn buttons(&self) -> Option<&Buttons> {
+        Some(&self.buttons)
+    }
+
+    fn submit(&self) -> Option<PopupReqParam> {
         let url = Url::parse(&format!("{}://{}:{}/3.1/lists/{}",
             self.config.protocol(),
             self.config.host(),
@@ -65,6 +67,10 @@ impl Popup for ListDel<'_> {
             self.config.list().unwrap().list_id())).unwrap();
         let map = HashMap::new();
 
-        PopupReqParam::new(Method::DELETE, url, map)
+        Some(PopupReqParam::new(Method::DELETE, url, map))
+    }
+
+    fn undo(&self) -> Option<Undo> {
+        Some(Undo::Impossible(format!("deleting {}", self.config.list().unwrap().fqdn_listname())))
     }
 }
\ No newline at end of file
@@ -0,0 +1,71 @@
+use ratatui::{prelude::*, widgets::*};
+use tui_textarea::{Input, Key};
+
+use crate::{config::Config, lists, popup::{self, Buttons, Popup, PopupStatus}, Action};
+
+#[derive(Clone)]
+pub struct ListDetail<'a> {
+    paragraph: Paragraph<'a>,
+    buttons: Buttons,
+}
+
+impl<'a> ListDetail<'a> {
+    pub fn new(config: Config) -> Self {
+        let list = config.list().unwrap();
+        let mut text: Vec<Line> = lists::Entry::COLUMNS.iter().map(|column| {
+            Line::from(format!("{:<14} {}", column, list.column(column).unwrap_or_default()))
+        }).collect();
+        text.push(Line::from(""));
+        text.push(Line::from("Type 'm' for members, 'b' for bounces, 'r' to sync members from a file"));
+        text.push(Line::from("'s' for held messages, 't' for templates, 'h' for header filters"));
+        text.push(Line::from("'v' for archivers, 'i' for acceptable aliases, 'g' for the digest"));
+        text.push(Line::from("'c' to clone another list's settings, 'w' to back up or restore"));
+        text.push(Line::from("'e' to inject a message, Esc to close"));
+        let paragraph = Paragraph::new(text)
+            .block(Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(format!(" {} ", list.display_name()))
+            .style(Style::default().fg(Color::Blue)),
+        );
+
+        Self {
+            paragraph,
+            buttons: Buttons::new(&[("Members", Key::Char('m')), ("Bounces", Key::Char('b')), ("Held", Key::Char('s')), ("Templates", Key::Char('t')), ("Filters", Key::Char('h')), ("Archivers", Key::Char('v')), ("Close", Key::Esc)]),
+        }
+    }
+}
+
+impl Popup for ListDetail<'_> {
+    fn render(&mut self, frame: &mut Frame) {
+        let area = popup::area(frame, 80, lists::Entry::COLUMNS.len() as u16 + 9);
+
+        frame.render_widget(self.paragraph.clone(), area);
+        self.buttons.render(frame, area);
+    }
+
+    fn input(&mut self, input: Input) -> PopupStatus {
+        match input {
+            Input { key: Key::Esc, .. } |
+            Input { key: Key::Enter, .. } => PopupStatus::Cancel,
+            Input { key: Key::Char('m'), .. } => PopupStatus::Action(Action::Members),
+            Input { key: Key::Char('r'), .. } => PopupStatus::Action(Action::Sync),
+            Input { key: Key::Char('s'), .. } => PopupStatus::Action(Action::Messages),
+            Input { key: Key::Char('t'), .. } => PopupStatus::Action(Action::ListTemplates),
+            Input { key: Key::Char('h'), .. } => PopupStatus::Action(Action::HeaderMatches),
+            Input { key: Key::Char('v'), .. } => PopupStatus::Action(Action::Archivers),
+            Input { key: Key::Char('i'), .. } => PopupStatus::Action(Action::Aliases),
+            Input { key: Key::Char('b'), .. } => PopupStatus::Action(Action::Bounces),
+            Input { key: Key::Char('g'), .. } => PopupStatus::Action(Action::Digest),
+            Input { key: Key::Char('e'), .. } => PopupStatus::Action(Action::Inject),
+            Input { key: Key::Char('c'), .. } => PopupStatus::Action(Action::CloneConfig),
+            Input { key: Key::Char('w'), .. } => PopupStatus::Action(Action::BackupRestore),
+            _input => PopupStatus::Continue,
+        }
+    }
+
+    fn buttons(&self) -> Option<&Buttons> {
+        Some(&self.buttons)
+    }
+
+}
@@ -23,12 +23,16 @@ pub struct Entry {
 }
 
 impl Lists {
-    pub fn list_vec(&self) -> Vec<String> {
-        if let Some(entries) = &self.entries {
-            entries.iter().map(|entry| entry.fqdn_listname.clone()).collect()
+    pub fn table(&self, columns: &[String]) -> (Vec<String>, Vec<Vec<String>>) {
+        let columns: Vec<String> = columns.iter().filter(|c| Entry::COLUMNS.contains(&c.as_str())).cloned().collect();
+        let rows = if let Some(entries) = &self.entries {
+            entries.iter().map(|entry| {
+                columns.iter().map(|column| entry.column(column).unwrap_or_default()).collect()
+            }).collect()
         } else {
             Vec::new()
-        }
+        };
+        (columns, rows)
     }
 
     pub fn entries(&self) -> Option<Vec<Entry>> {
@@ -37,6 +41,31 @@ impl Lists {
 }
 
 impl Entry {
+    pub const COLUMNS: [&'static str; 8] = [
+        "fqdn_listname",
+        "display_name",
+        "list_id",
+        "mail_host",
+        "description",
+        "member_count",
+        "volume",
+        "advertised",
+    ];
+
+    pub fn column(&self, column: &str) -> Option<String> {
+        match column {
+            "fqdn_listname" => Some(self.fqdn_listname.clone()),
+            "display_name" => Some(self.display_name.clone()),
+            "list_id" => Some(self.list_id.clone()),
+            "mail_host" => Some(self.mail_host.clone()),
+            "description" => Some(self.description.clone().unwrap_or_default()),
+            "member_count" => Some(self.member_count.to_string()),
+            "volume" => Some(self.volume.to_string()),
+            "advertised" => Some(self.advertised.to_string()),
+            _ => None,
+        }
+    }
+
     pub fn display_name(&self) -> String {
         self.display_name.clone()
     }
@@ -4,12 +4,15 @@ use ratatui::{prelude::*, widgets::*};
 use reqwest::{Method, Url};
 use tui_textarea::{TextArea, Input, Key};
 
-use crate::{config::Config, popup::{Popup, PopupReqParam, PopupStatus}};
+use crate::{addresses, config::Config, popup::{self, Popup, PopupReqParam, PopupStatus}};
 
 #[derive(Clone)]
 pub struct MemberAdd<'a> {
     config: Config,
     text_area: TextArea<'a>,
+    // Addresses and display names of a paste holding several of them
+    pasted: Option<Vec<(String, String)>>,
+    scroll: u16,
 }
 
 impl<'a> MemberAdd<'a> {
@@ -25,24 +28,73 @@ impl<'a> MemberAdd<'a> {
         Self {
             config,
             text_area,
+            pasted: None,
+            scroll: 0,
         }
     }
+
+    fn subscribe(&self, subscriber: String, display_name: String) -> PopupReqParam {
+        let url = Url::parse(&format!("{}://{}:{}/3.1/members",
+            self.config.protocol(),
+            self.config.host(),
+            self.config.port())).unwrap();
+        
+        let mut map = HashMap::new();
+        map.insert("list_id".to_string(), self.config.list().unwrap().list_id());
+        map.insert("subscriber".to_string(), subscriber.clone());
+        map.insert("display_name".to_string(), display_name);
+        map.insert("pre_verified".to_string(), "true".to_string());
+        map.insert("pre_confirmed".to_string(), "true".to_string());
+        map.insert("pre_approved".to_string(), "true".to_string());
+        map.insert("send_welcome_message".to_string(), "false".to_string());
+
+        PopupReqParam::new(Method::POST, url, map).with_label(subscriber)
+    }
 }
 
 impl Popup for MemberAdd<'_> {
     fn render(&mut self, frame: &mut Frame) {
-        let area = Rect {
-            width: 80,
-            height: 3,
-            x: 42,
-            y: 20,
-        };
+        if let Some(pasted) = &self.pasted {
+            let text: Vec<Line> = pasted.iter().map(|(address, display_name)| {
+                if display_name.is_empty() {
+                    Line::raw(address.clone())
+                } else {
+                    Line::raw(format!("{} ({})", address, display_name))
+                }
+            }).collect();
+            // The list and a hint below it
+            let area = popup::area(frame, 80, (pasted.len() as u16 + 2).min(14) + 1);
+            let chunks = Layout::vertical([Constraint::Min(3), Constraint::Length(1)]).split(area);
+            let paragraph = Paragraph::new(text)
+                .scroll((self.scroll, 0))
+                .block(Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .title(format!(" Subscribe {} Pasted Addresses? ", pasted.len()))
+                .style(Style::default().fg(Color::Blue)),
+            );
+            frame.render_widget(paragraph, chunks[0]);
+            let hint = Paragraph::new("Enter: subscribe all, Up/Down: scroll, Esc: back").fg(Color::LightRed);
+            frame.render_widget(hint, chunks[1]);
+            return;
+        }
 
+        let area = popup::area(frame, 80, 3);
         frame.render_widget(&self.text_area, area);
     }
 
     fn input(&mut self, input: Input) -> PopupStatus {
         let mut status = PopupStatus::Continue;
+        if self.pasted.is_some() {
+            match input {
+                Input { key: Key::Esc, .. } => self.pasted = None,
+                Input { key: Key::Enter, .. } => status = PopupStatus::Submit,
+                Input { key: Key::Down, .. } => self.scroll = self.scroll.saturating_add(1),
+                Input { key: Key::Up, .. } => self.scroll = self.scroll.saturating_sub(1),
+                _input => {}
+            }
+            return status;
+        }
         match input {
             Input { key: Key::Esc, .. } => status = PopupStatus::Cancel,
             Input { key: Key::Enter, .. } => status = PopupStatus::Submit,
@@ -52,21 +104,26 @@ impl Popup for MemberAdd<'_> {
         status
     }
 
-    fn submit(&self) -> PopupReqParam {
-        let url = Url::parse(&format!("{}://{}:{}/3.1/members",
-            self.config.protocol(),
-            self.config.host(),
-            self.config.port())).unwrap();
-        
-        let mut map = HashMap::new();
-        map.insert("list_id".to_string(), self.config.list().unwrap().list_id());
-        map.insert("subscriber".to_string(), self.text_area.lines()[0].clone());
-        map.insert("display_name".to_string(), "".to_string());
-        map.insert("pre_verified".to_string(), "true".to_string());
-        map.insert("pre_confirmed".to_string(), "true".to_string());
-        map.insert("pre_approved".to_string(), "true".to_string());
-        map.insert("send_welcome_message".to_string(), "false".to_string());
+    // A single address goes into the field like typed, several are offered to be subscribed at once
+    fn paste(&mut self, text: &str) {
+        let pasted = addresses::parse_named(text);
+        if pasted.len() > 1 {
+            self.pasted = Some(pasted);
+            self.scroll = 0;
+        } else {
+            popup::paste_line(&mut self.text_area, text);
+        }
+    }
 
-        PopupReqParam::new(Method::POST, url, map)
+    fn submit_all(&self) -> Vec<PopupReqParam> {
+        match &self.pasted {
+            Some(pasted) => pasted.iter().map(|(address, display_name)| self.subscribe(address.clone(), display_name.clone())).collect(),
+            None => vec![self.subscribe(self.text_area.lines()[0].clone(), "".to_string())],
+        }
+    }
+
+    // Some of the pasted addresses may be subscribed already; the others shouldn't wait for them
+    fn batch(&self) -> bool {
+        self.pasted.is_some()
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,244 @@
+use std::collections::HashMap;
+
+use ratatui::{prelude::*, widgets::*};
+use reqwest::{Method, Url};
+use tui_textarea::{Input, Key};
+
+use crate::{config::Config, lists, members::{self, Members}, popup::{self, Popup, PopupReqParam, PopupStatus}, response::{Response, ResponseType}, undo::{self, Undo}};
+
+#[derive(Clone)]
+pub struct MemberCopy {
+    config: Config,
+    members: Vec<members::Entry>,
+    lists: Vec<lists::Entry>,
+    target: usize,
+    move_members: bool,
+    // The target's members, once it was chosen and its roster loaded
+    target_roster: Option<Vec<members::Entry>>,
+    loading: bool,
+    scroll: u16,
+    error: Option<String>,
+}
+
+impl MemberCopy {
+    pub fn new(config: Config, members: Vec<members::Entry>, lists: Vec<lists::Entry>) -> Self {
+        // Copying to the list the members come from would only produce duplicates
+        let source = config.list().map(|list| list.list_id());
+        let lists = lists.into_iter().filter(|list| Some(list.list_id()) != source).collect();
+
+        Self {
+            config,
+            members,
+            lists,
+            target: 0,
+            move_members: false,
+            target_roster: None,
+            loading: false,
+            scroll: 0,
+            error: None,
+        }
+    }
+
+    fn url(&self, path: &str) -> Url {
+        Url::parse(&format!("{}://{}:{}/3.1/{}",
+            self.config.protocol(),
+            self.config.host(),
+            self.config.port(),
+            path)).unwrap()
+    }
+
+    fn is_duplicate(&self, member: &members::Entry) -> bool {
+        self.target_roster.as_ref().is_some_and(|roster| {
+            roster.iter().any(|m| m.email().to_lowercase() == member.email().to_lowercase())
+        })
+    }
+
+    fn render_preview(&self, frame: &mut Frame, area: Rect) {
+        let target = self.lists[self.target].fqdn_listname();
+        let (duplicates, copies): (Vec<&members::Entry>, Vec<&members::Entry>) = self.members.iter().partition(|m| self.is_duplicate(m));
+        let mut text = vec![Line::styled(format!("Will subscribe {} members to {}:", copies.len(), target), Style::default().fg(Color::LightRed))];
+        text.extend(copies.iter().map(|member| Line::raw(format!("+ {} ({})", member.email(), member.delivery_mode()))));
+        if !duplicates.is_empty() {
+            text.push(Line::from(""));
+            text.push(Line::styled(format!("Already subscribed, skipped ({}):", duplicates.len()), Style::default().fg(Color::LightRed)));
+            text.extend(duplicates.iter().map(|member| Line::raw(format!("= {}", member.email()))));
+        }
+        if self.move_members {
+            text.push(Line::from(""));
+            text.push(Line::raw("Afterwards all of them are unsubscribed from this list"));
+        }
+        let paragraph = Paragraph::new(text)
+            .scroll((self.scroll, 0))
+            .block(Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(" Preview (Up/Down: scroll, 'y' or Enter: go, Esc: back) ".to_string())
+            .style(Style::default().fg(Color::Blue)),
+        );
+        frame.render_widget(paragraph, area);
+    }
+}
+
+impl Popup for MemberCopy {
+    fn render(&mut self, frame: &mut Frame) {
+        let area = popup::area(frame, 80, 18);
+
+        if self.target_roster.is_some() {
+            self.render_preview(frame, area);
+            return;
+        }
+
+        let mut text = vec![
+            Line::raw(format!("{} {} members to:", if self.move_members { "Move" } else { "Copy" }, self.members.len())),
+            Line::from(""),
+        ];
+        text.extend(self.lists.iter().enumerate().map(|(i, list)| {
+            if i == self.target {
+                Line::styled(format!("> {}", list.fqdn_listname()), Style::default().fg(Color::Red).add_modifier(Modifier::BOLD))
+            } else {
+                Line::raw(format!("  {}", list.fqdn_listname()))
+            }
+        }));
+        text.push(Line::from(""));
+        match &self.error {
+            Some(error) => text.push(Line::styled(error.clone(), Style::default().fg(Color::Red))),
+            None => text.push(Line::raw("Up/Down: target, 'm': copy or move, Enter: preview, Esc: cancel")),
+        }
+        let paragraph = Paragraph::new(text)
+            .block(Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(" Copy Members ".to_string())
+            .style(Style::default().fg(Color::Blue)),
+        );
+        frame.render_widget(paragraph, area);
+    }
+
+    fn input(&mut self, input: Input) -> PopupStatus {
+        let mut status = PopupStatus::Continue;
+        if self.target_roster.is_some() {
+            match input {
+                Input { key: Key::Esc, .. } |
+                Input { key: Key::Char('n'), .. } => self.target_roster = None,
+                Input { key: Key::Enter, .. } |
+                Input { key: Key::Char('y'), .. } => status = PopupStatus::Submit,
+                Input { key: Key::Down, .. } => self.scroll = self.scroll.saturating_add(1),
+                Input { key: Key::Up, .. } => self.scroll = self.scroll.saturating_sub(1),
+                _input => {}
+            }
+            return status;
+        }
+        match input {
+            Input { key: Key::Esc, .. } => status = PopupStatus::Cancel,
+            Input { key: Key::Enter, .. } if !self.lists.is_empty() => {
+                self.loading = true;
+                self.error = Some("Loading the target's members...".to_string());
+                status = PopupStatus::Load;
+            }
+            Input { key: Key::Down, .. } if !self.lists.is_empty() => self.target = (self.target + 1) % self.lists.len(),
+            Input { key: Key::Up, .. } if !self.lists.is_empty() => self.target = (self.target + self.lists.len() - 1) % self.lists.len(),
+            Input { key: Key::Char('m'), .. } |
+            Input { key: Key::Char(' '), .. } => self.move_members = !self.move_members,
+            _input => {}
+        }
+
+        status
+    }
+
+    fn submit_all(&self) -> Vec<PopupReqParam> {
+        let target = &self.lists[self.target];
+        let mut params = Vec::new();
+        for member in &self.members {
+            let duplicate = self.is_duplicate(member);
+            if !duplicate {
+                let mut map = HashMap::new();
+                map.insert("list_id".to_string(), target.list_id());
+                map.insert("subscriber".to_string(), member.email());
+                map.insert("display_name".to_string(), member.display_name());
+                map.insert("delivery_mode".to_string(), member.delivery_mode());
+                map.insert("role".to_string(), member.role());
+                map.insert("pre_verified".to_string(), "true".to_string());
+                map.insert("pre_confirmed".to_string(), "true".to_string());
+                map.insert("pre_approved".to_string(), "true".to_string());
+                map.insert("send_welcome_message".to_string(), "false".to_string());
+                params.push(PopupReqParam::new(Method::POST, self.url("members"), map)
+                    .with_label(format!("subscribing {}", member.email())));
+            }
+            if self.move_members {
+                let mut map = HashMap::new();
+                map.insert("pre_approved".to_string(), "true".to_string());
+                map.insert("pre_confirmed".to_string(), "true".to_string());
+                let param = PopupReqParam::new(Method::DELETE, self.url(&format!("members/{}", member.member_id())), map)
+                    .with_label(format!("unsubscribing {}", member.email()));
+                // Never lose a member because subscribing them to the target failed
+                if duplicate {
+                    params.push(param);
+                } else {
+                    params.push(param.if_previous_succeeded());
+                }
+            }
+        }
+        params
+    }
+
+    fn batch(&self) -> bool {
+        true
+    }
+
+    // Copying removes nothing, moving gets the members back and, once they are, takes out
+    // the copies that didn't exist before
+    fn undo(&self) -> Option<Undo> {
+        if !self.move_members {
+            return None;
+        }
+        let target = &self.lists[self.target];
+        let mut params = Vec::new();
+        for member in &self.members {
+            params.extend(undo::resubscribe(&self.config, member, None));
+            if !self.is_duplicate(member) {
+                let path = format!("lists/{}/{}/{}", target.list_id(), member.role(), member.email());
+                let mut map = HashMap::new();
+                map.insert("pre_approved".to_string(), "true".to_string());
+                map.insert("pre_confirmed".to_string(), "true".to_string());
+                params.push(PopupReqParam::new(Method::DELETE, self.url(&path), map)
+                    .with_label(format!("unsubscribing {} from {}", member.email(), target.fqdn_listname()))
+                    .if_previous_succeeded());
+            }
+        }
+        Some(Undo::Restore(format!("moving {} members to {} (without their preferences)", self.members.len(), target.fqdn_listname()), params))
+    }
+
+    fn batch_note(&self) -> Option<String> {
+        let duplicates = self.members.iter().filter(|m| self.is_duplicate(m)).count();
+        if duplicates > 0 {
+            Some(format!("{} already subscribed to {}", duplicates, self.lists[self.target].fqdn_listname()))
+        } else {
+            None
+        }
+    }
+
+    // Nothing to load until the target is chosen
+    fn load(&self) -> Vec<PopupReqParam> {
+        if !self.loading {
+            return Vec::new();
+        }
+        let url = self.url(&format!("lists/{}/roster/member", self.lists[self.target].list_id()));
+        vec![PopupReqParam::new_for(Method::GET, url, HashMap::new(), ResponseType::PopupData("roster".to_string()))]
+    }
+
+    fn loaded(&mut self, response: Response) {
+        self.loading = false;
+        if !response.is_success() {
+            self.error = Some(format!("Can't load the target's members: {}", response.status()));
+            return;
+        }
+        match serde_json::from_str::<Members>(&response.text()) {
+            Ok(members) => {
+                self.target_roster = Some(members.entries().unwrap_or_default());
+                self.scroll = 0;
+                self.error = None;
+            }
+            Err(e) => self.error = Some(format!("Can't read the target's members: {}", e)),
+        }
+    }
+}
@@ -2,14 +2,18 @@ use std::collections::HashMap;
 
 use ratatui::{prelude::*, widgets::*};
 use reqwest::{Method, Url};
+use serde_json::{Map, value::Value};
 use tui_textarea::{Input, Key};
 
-use crate::{config::Config, popup::{Popup, PopupReqParam, PopupStatus}};
+use crate::{backup, config::Config, popup::{self, Buttons, Popup, PopupReqParam, PopupStatus}, response::{Response, ResponseType}, undo::{self, Undo}};
 
 #[derive(Clone)]
 pub struct MemberDel<'a> {
     config: Config,
     paragraph: Paragraph<'a>,
+    buttons: Buttons,
+    // Read before deleting, so an undo can put them back
+    preferences: Option<Map<String, Value>>,
 }
 
 impl<'a> MemberDel<'a> {
@@ -26,20 +30,18 @@ impl<'a> MemberDel<'a> {
         Self {
             config,
             paragraph,
+            buttons: Buttons::new(&[("Yes", Key::Enter), ("No", Key::Esc)]),
+            preferences: None,
         }
     }
 }
 
 impl Popup for MemberDel<'_> {
     fn render(&mut self, frame: &mut Frame) {
-        let area = Rect {
-            width: 62,
-            height: 3,
-            x: 42,
-            y: 20,
-        };
+        let area = popup::area(frame, 62, 4);
 
         frame.render_widget(self.paragraph.clone(), area);
+        self.buttons.render(frame, area);
     }
 
     fn input(&mut self, input: Input) -> PopupStatus {
@@ -57,7 +59,11 @@ impl Popup for MemberDel<'_> {
         status
     }
 
-    fn submit(&self) -> PopupReqParam {
+    fn buttons(&self) -> Option<&Buttons> {
+        Some(&self.buttons)
+    }
+
+    fn submit(&self) -> Option<PopupReqParam> {
         let url = Url::parse(&format!("{}://{}:{}/3.1/members/{}",
             self.config.protocol(),
             self.config.host(),
@@ -65,6 +71,32 @@ impl Popup for MemberDel<'_> {
             self.config.member().unwrap().member_id())).unwrap();
         let map = HashMap::new();
 
-        PopupReqParam::new(Method::DELETE, url, map)
+        Some(PopupReqParam::new(Method::DELETE, url, map))
+    }
+
+    fn load(&self) -> Vec<PopupReqParam> {
+        let url = Url::parse(&format!("{}://{}:{}/3.1/members/{}/preferences",
+            self.config.protocol(),
+            self.config.host(),
+            self.config.port(),
+            self.config.member().unwrap().member_id())).unwrap();
+
+        vec![PopupReqParam::new_for(Method::GET, url, HashMap::new(), ResponseType::PopupData("preferences".to_string()))]
+    }
+
+    fn loaded(&mut self, response: Response) {
+        if response.is_success() {
+            self.preferences = serde_json::from_str(&response.text()).ok().map(backup::strip);
+        }
+    }
+
+    fn undo(&self) -> Option<Undo> {
+        let member = self.config.member().unwrap();
+        // Confirming before the preferences arrived, or when they couldn't be read, loses them
+        let mut description = format!("deleting {} from {}", member.email(), member.list_id());
+        if self.preferences.is_none() {
+            description.push_str(" (without preferences)");
+        }
+        Some(Undo::Restore(description, undo::resubscribe(&self.config, &member, self.preferences.as_ref())))
     }
 }
\ No newline at end of file
@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+
+use ratatui::{prelude::*, widgets::*};
+use reqwest::{Method, Url};
+use tui_textarea::{TextArea, Input, Key};
+
+use crate::{config::Config, popup::{self, Popup, PopupReqParam, PopupStatus}, response::ResponseType};
+
+const FIELDS: [(&str, &str); 3] = [
+    ("subscriber", " Subscriber (use * as wildcard) "),
+    ("role", " Role (member, owner, moderator, nonmember) "),
+    ("list_id", " List ID "),
+];
+
+#[derive(Clone)]
+pub struct MemberFind<'a> {
+    config: Config,
+    text_areas: Vec<TextArea<'a>>,
+    focus: usize,
+}
+
+impl<'a> MemberFind<'a> {
+    pub fn new(config: Config) -> Self {
+        let mut text_areas: Vec<TextArea> = FIELDS.iter().map(|(_, title)| {
+            let mut text_area = TextArea::default();
+            text_area.set_block(Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .title(title.to_string())
+                .style(Style::default().fg(Color::Blue)),
+            );
+            text_area
+        }).collect();
+        if let Some(list) = config.list() {
+            text_areas[2].insert_str(list.list_id());
+        }
+
+        let mut member_find = Self {
+            config,
+            text_areas,
+            focus: 0,
+        };
+        member_find.set_focus(0);
+        member_find
+    }
+
+    fn set_focus(&mut self, focus: usize) {
+        self.focus = focus;
+        for (i, text_area) in self.text_areas.iter_mut().enumerate() {
+            if i == focus {
+                text_area.set_cursor_style(Style::default().add_modifier(Modifier::REVERSED));
+            } else {
+                text_area.set_cursor_style(Style::default());
+            }
+        }
+    }
+}
+
+impl Popup for MemberFind<'_> {
+    fn render(&mut self, frame: &mut Frame) {
+        let area = popup::area(frame, 80, 3 * self.text_areas.len() as u16);
+        let chunks = Layout::vertical(vec![Constraint::Length(3); self.text_areas.len()]).split(area);
+        for (text_area, chunk) in self.text_areas.iter().zip(chunks.iter()) {
+            frame.render_widget(text_area, *chunk);
+        }
+    }
+
+    fn input(&mut self, input: Input) -> PopupStatus {
+        let mut status = PopupStatus::Continue;
+        match input {
+            Input { key: Key::Esc, .. } => status = PopupStatus::Cancel,
+            Input { key: Key::Enter, .. } => status = PopupStatus::Submit,
+            Input { key: Key::Tab, .. } |
+            Input { key: Key::Down, .. } => self.set_focus((self.focus + 1) % self.text_areas.len()),
+            Input { key: Key::Up, .. } => self.set_focus((self.focus + self.text_areas.len() - 1) % self.text_areas.len()),
+            input => { self.text_areas[self.focus].input(input); }
+        }
+
+        status
+    }
+
+    fn paste(&mut self, text: &str) {
+        popup::paste_line(&mut self.text_areas[self.focus], text);
+    }
+
+    fn submit(&self) -> Option<PopupReqParam> {
+        let url = Url::parse(&format!("{}://{}:{}/3.1/members/find",
+            self.config.protocol(),
+            self.config.host(),
+            self.config.port())).unwrap();
+
+        let mut map = HashMap::new();
+        for ((key, _), text_area) in FIELDS.iter().zip(self.text_areas.iter()) {
+            let value = text_area.lines()[0].trim();
+            if !value.is_empty() {
+                map.insert(key.to_string(), value.to_string());
+            }
+        }
+
+        Some(PopupReqParam::new_for(Method::POST, url, map, ResponseType::Members))
+    }
+}
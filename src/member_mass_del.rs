@@ -0,0 +1,309 @@
+use std::collections::HashMap;
+
+use ratatui::{prelude::*, widgets::*};
+use reqwest::{Method, Url};
+use serde_json::value::Value;
+use tui_textarea::{CursorMove, TextArea, Input, Key};
+
+use crate::{addresses, config::Config, members::{self, Members}, popup::{self, Popup, PopupReqParam, PopupStatus}, response::{Response, ResponseType}, undo::{self, Undo}};
+
+#[derive(Clone)]
+pub struct MemberMassDel<'a> {
+    config: Config,
+    addresses: TextArea<'a>,
+    file: TextArea<'a>,
+    goodbye: bool,
+    // Once the user toggled goodbye notices, the list's setting no longer overrides them
+    goodbye_changed: bool,
+    focus: usize,
+    roster: Option<Vec<members::Entry>>,
+    send_goodbye_message: Option<bool>,
+    // Members to unsubscribe and addresses that aren't subscribed, once the dry run was done
+    dry_run: Option<(Vec<members::Entry>, Vec<String>)>,
+    scroll: u16,
+    error: Option<String>,
+}
+
+impl<'a> MemberMassDel<'a> {
+    // Starts with the addresses marked in the members view, if any
+    pub fn new(config: Config, marked: Vec<String>) -> Self {
+        let mut addresses = TextArea::new(marked);
+        addresses.set_block(Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(" Addresses to unsubscribe (paste or type, any separator) ".to_string())
+            .style(Style::default().fg(Color::Blue)),
+        );
+        let mut file = TextArea::default();
+        file.set_cursor_line_style(Style::default());
+        file.set_block(Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(" Import from file (Enter adds its addresses) ".to_string())
+            .style(Style::default().fg(Color::Blue)),
+        );
+
+        let mut member_mass_del = Self {
+            config,
+            addresses,
+            file,
+            goodbye: false,
+            goodbye_changed: false,
+            focus: 0,
+            roster: None,
+            send_goodbye_message: None,
+            dry_run: None,
+            scroll: 0,
+            error: None,
+        };
+        member_mass_del.set_focus(0);
+        member_mass_del
+    }
+
+    fn set_focus(&mut self, focus: usize) {
+        self.focus = focus;
+        for (i, text_area) in [&mut self.addresses, &mut self.file].into_iter().enumerate() {
+            if i == focus {
+                text_area.set_cursor_style(Style::default().add_modifier(Modifier::REVERSED));
+            } else {
+                text_area.set_cursor_style(Style::default());
+            }
+        }
+    }
+
+    fn url(&self, path: &str) -> Url {
+        Url::parse(&format!("{}://{}:{}/3.1/{}",
+            self.config.protocol(),
+            self.config.host(),
+            self.config.port(),
+            path)).unwrap()
+    }
+
+    fn list_url(&self, path: &str) -> Url {
+        self.url(&format!("lists/{}/{}", self.config.list().unwrap().list_id(), path))
+    }
+
+    fn import(&mut self) {
+        let path = self.file.lines()[0].trim().to_string();
+        match std::fs::read_to_string(&path) {
+            Ok(text) => {
+                let imported = addresses::parse(&text);
+                self.error = Some(format!("Imported {} addresses from {}", imported.len(), path));
+                self.addresses.move_cursor(CursorMove::Bottom);
+                self.addresses.move_cursor(CursorMove::End);
+                for address in imported {
+                    self.addresses.insert_newline();
+                    self.addresses.insert_str(address);
+                }
+            }
+            Err(e) => self.error = Some(format!("Can't read {}: {}", path, e)),
+        }
+    }
+
+    fn dry_run(&mut self) {
+        let Some(roster) = &self.roster else {
+            self.error = Some("The members of the list haven't been loaded yet".to_string());
+            return;
+        };
+        let wanted = addresses::parse(&self.addresses.lines().join("\n"));
+        if wanted.is_empty() {
+            self.error = Some("No addresses to unsubscribe".to_string());
+            return;
+        }
+        let removals: Vec<members::Entry> = roster.iter()
+            .filter(|member| wanted.contains(&member.email().to_lowercase()))
+            .cloned()
+            .collect();
+        let unknown = wanted.into_iter()
+            .filter(|address| !removals.iter().any(|member| member.email().to_lowercase() == *address))
+            .collect();
+        self.dry_run = Some((removals, unknown));
+        self.scroll = 0;
+        self.error = None;
+    }
+
+    fn render_dry_run(&self, frame: &mut Frame, area: Rect) {
+        let Some((removals, unknown)) = &self.dry_run else {
+            return;
+        };
+        let mut text = vec![Line::styled(format!("Will unsubscribe {} members:", removals.len()), Style::default().fg(Color::LightRed))];
+        text.extend(removals.iter().map(|member| Line::raw(format!("- {}", member.email()))));
+        if !unknown.is_empty() {
+            text.push(Line::from(""));
+            text.push(Line::styled(format!("Not subscribed, skipped ({}):", unknown.len()), Style::default().fg(Color::LightRed)));
+            text.extend(unknown.iter().map(|address| Line::raw(format!("? {}", address))));
+        }
+        text.push(Line::from(""));
+        text.push(Line::raw(match self.send_goodbye_message {
+            Some(_) => format!("Goodbye notices: {}", if self.goodbye { "yes" } else { "no" }),
+            // Without the list's setting there is nothing to switch from and back to
+            None => "Goodbye notices: as the list is set, its settings weren't loaded so the checkbox is ignored".to_string(),
+        }));
+        let hint = if removals.is_empty() {
+            " Dry run: nothing to do (Esc: back) ".to_string()
+        } else {
+            " Dry run (Up/Down: scroll, 'y' or Enter: unsubscribe, Esc: back) ".to_string()
+        };
+        let paragraph = Paragraph::new(text)
+            .scroll((self.scroll, 0))
+            .block(Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(hint)
+            .style(Style::default().fg(Color::Blue)),
+        );
+        frame.render_widget(paragraph, area);
+    }
+}
+
+impl Popup for MemberMassDel<'_> {
+    fn render(&mut self, frame: &mut Frame) {
+        let area = popup::area(frame, 80, if self.dry_run.is_some() { 18 } else { 15 });
+
+        if self.dry_run.is_some() {
+            self.render_dry_run(frame, area);
+            return;
+        }
+
+        let chunks = Layout::vertical([Constraint::Min(3), Constraint::Length(3), Constraint::Length(2)]).split(area);
+        frame.render_widget(&self.addresses, chunks[0]);
+        frame.render_widget(&self.file, chunks[1]);
+        let mut goodbye_style = Style::default().fg(Color::LightRed);
+        if self.focus == 2 {
+            goodbye_style = goodbye_style.add_modifier(Modifier::REVERSED);
+        }
+        let roster = match &self.roster {
+            Some(roster) => format!("{} members loaded", roster.len()),
+            None => "loading members...".to_string(),
+        };
+        let text = vec![
+            Line::from(vec![
+                Span::styled(format!("{} Send goodbye notices", if self.goodbye { "[x]" } else { "[ ]" }), goodbye_style),
+                Span::raw(format!("  ({})", roster)),
+            ]),
+            match &self.error {
+                Some(error) => Line::styled(error.clone(), Style::default().fg(Color::Red)),
+                None => Line::raw("Tab: next field, Ctrl-S: dry run, Esc: cancel"),
+            },
+        ];
+        frame.render_widget(Paragraph::new(text), chunks[2]);
+    }
+
+    fn input(&mut self, input: Input) -> PopupStatus {
+        let mut status = PopupStatus::Continue;
+        if let Some((removals, _)) = &self.dry_run {
+            match input {
+                Input { key: Key::Esc, .. } |
+                Input { key: Key::Char('n'), .. } => self.dry_run = None,
+                Input { key: Key::Enter, .. } |
+                Input { key: Key::Char('y'), .. } if !removals.is_empty() => status = PopupStatus::Submit,
+                Input { key: Key::Down, .. } => self.scroll = self.scroll.saturating_add(1),
+                Input { key: Key::Up, .. } => self.scroll = self.scroll.saturating_sub(1),
+                _input => {}
+            }
+            return status;
+        }
+        match input {
+            Input { key: Key::Esc, .. } => status = PopupStatus::Cancel,
+            Input { key: Key::Char('s'), ctrl: true, .. } => self.dry_run(),
+            Input { key: Key::Tab, .. } => self.set_focus((self.focus + 1) % 3),
+            input => match self.focus {
+                0 => { self.addresses.input(input); }
+                1 => match input {
+                    Input { key: Key::Enter, .. } => self.import(),
+                    input => { self.file.input(input); }
+                },
+                _ => if let Input { key: Key::Left | Key::Right | Key::Char(' '), .. } = input {
+                    self.goodbye = !self.goodbye;
+                    self.goodbye_changed = true;
+                },
+            }
+        }
+
+        status
+    }
+
+    // The address field takes a pasted list as it is, one address per line or not
+    fn paste(&mut self, text: &str) {
+        if self.dry_run.is_some() {
+            return;
+        }
+        match self.focus {
+            0 => { self.addresses.insert_str(text); }
+            1 => popup::paste_line(&mut self.file, text),
+            _ => {}
+        }
+    }
+
+    // Mailman sends goodbye notices according to the list's send_goodbye_message,
+    // so that is switched for the duration of the batch if needed and restored afterwards.
+    // Nobody is unsubscribed if the switch fails.
+    fn submit_all(&self) -> Vec<PopupReqParam> {
+        let mut params = Vec::new();
+        let switch = self.send_goodbye_message.filter(|original| *original != self.goodbye);
+        if switch.is_some() {
+            let mut map = HashMap::new();
+            map.insert("send_goodbye_message".to_string(), self.goodbye.to_string());
+            params.push(PopupReqParam::new(Method::PATCH, self.list_url("config"), map)
+                .with_label("switching goodbye notices".to_string())
+                .required());
+        }
+        if let Some((removals, _)) = &self.dry_run {
+            for member in removals {
+                // Without these Mailman would ask each member to confirm, depending on the list's policy
+                let mut map = HashMap::new();
+                map.insert("pre_approved".to_string(), "true".to_string());
+                map.insert("pre_confirmed".to_string(), "true".to_string());
+                params.push(PopupReqParam::new(Method::DELETE, self.url(&format!("members/{}", member.member_id())), map)
+                    .with_label(member.email()));
+            }
+        }
+        if let Some(original) = switch {
+            let mut map = HashMap::new();
+            map.insert("send_goodbye_message".to_string(), original.to_string());
+            params.push(PopupReqParam::new(Method::PATCH, self.list_url("config"), map)
+                .with_label("restoring goodbye notices".to_string()));
+        }
+        params
+    }
+
+    fn batch(&self) -> bool {
+        true
+    }
+
+    // Preferences would take a request per member to read, so only the memberships come back
+    fn undo(&self) -> Option<Undo> {
+        let (removals, _) = self.dry_run.as_ref()?;
+        let params = removals.iter().flat_map(|member| undo::resubscribe(&self.config, member, None)).collect();
+        Some(Undo::Restore(format!("unsubscribing {} members (without their preferences)", removals.len()), params))
+    }
+
+    fn load(&self) -> Vec<PopupReqParam> {
+        vec![
+            PopupReqParam::new_for(Method::GET, self.list_url("roster/member"), HashMap::new(), ResponseType::PopupData("roster".to_string())),
+            PopupReqParam::new_for(Method::GET, self.list_url("config"), HashMap::new(), ResponseType::PopupData("config".to_string())),
+        ]
+    }
+
+    fn loaded(&mut self, response: Response) {
+        let ResponseType::PopupData(data) = response.response_type() else {
+            return;
+        };
+        if !response.is_success() {
+            self.error = Some(format!("Can't load the list's {}: {}", data, response.status()));
+            return;
+        }
+        match data.as_str() {
+            "roster" => match serde_json::from_str::<Members>(&response.text()) {
+                Ok(members) => self.roster = Some(members.entries().unwrap_or_default()),
+                Err(e) => self.error = Some(format!("Can't read the members: {}", e)),
+            },
+            _ => if let Ok(value) = serde_json::from_str::<Value>(&response.text()) {
+                self.send_goodbye_message = value.get("send_goodbye_message").and_then(Value::as_bool);
+                if !self.goodbye_changed {
+                    self.goodbye = self.send_goodbye_message.unwrap_or_default();
+                }
+            },
+        }
+    }
+}
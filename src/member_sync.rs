@@ -0,0 +1,195 @@
+use std::collections::HashMap;
+
+use ratatui::{prelude::*, widgets::*};
+use reqwest::{Method, Url};
+use tui_textarea::{TextArea, Input, Key};
+
+use crate::{config::Config, members::{self, Members}, popup::{self, Popup, PopupReqParam, PopupStatus}, response::{Response, ResponseType}, roster_sync::{self, Plan}, undo::{self, Undo}};
+
+#[derive(Clone)]
+pub struct MemberSync<'a> {
+    config: Config,
+    file: TextArea<'a>,
+    never_remove: bool,
+    focus: usize,
+    roster: Option<Vec<members::Entry>>,
+    plan: Option<Plan>,
+    scroll: u16,
+    error: Option<String>,
+}
+
+impl<'a> MemberSync<'a> {
+    pub fn new(config: Config) -> Self {
+        let mut file = TextArea::default();
+        file.set_cursor_line_style(Style::default());
+        file.set_block(Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(" Source file (CSV or one address per line) ".to_string())
+            .style(Style::default().fg(Color::Blue)),
+        );
+
+        let mut member_sync = Self {
+            config,
+            file,
+            never_remove: false,
+            focus: 0,
+            roster: None,
+            plan: None,
+            scroll: 0,
+            error: None,
+        };
+        member_sync.set_focus(0);
+        member_sync
+    }
+
+    fn set_focus(&mut self, focus: usize) {
+        self.focus = focus;
+        if focus == 0 {
+            self.file.set_cursor_style(Style::default().add_modifier(Modifier::REVERSED));
+        } else {
+            self.file.set_cursor_style(Style::default());
+        }
+    }
+
+    fn plan(&mut self) {
+        let Some(roster) = &self.roster else {
+            self.error = Some("The members of the list haven't been loaded yet".to_string());
+            return;
+        };
+        let path = self.file.lines()[0].trim().to_string();
+        let source = match std::fs::read_to_string(&path) {
+            Ok(source) => source,
+            Err(e) => {
+                self.error = Some(format!("Can't read {}: {}", path, e));
+                return;
+            }
+        };
+        if let Err(e) = roster_sync::check_source(&source, self.never_remove) {
+            self.error = Some(e);
+            return;
+        }
+        self.plan = Some(Plan::new(&source, roster, self.never_remove));
+        self.scroll = 0;
+        self.error = None;
+    }
+}
+
+impl Popup for MemberSync<'_> {
+    fn render(&mut self, frame: &mut Frame) {
+        let area = popup::area(frame, 80, if self.plan.is_some() { 18 } else { 5 });
+
+        if let Some(plan) = &self.plan {
+            let mut text: Vec<Line> = plan.lines().into_iter().map(Line::raw).collect();
+            if plan.is_empty() {
+                text.push(Line::raw("The list is already in sync"));
+            }
+            let paragraph = Paragraph::new(text)
+                .scroll((self.scroll, 0))
+                .block(Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .title(format!(" Dry run: {} (Up/Down: scroll, 'y' or Enter: apply, Esc: back) ", plan.summary()))
+                .style(Style::default().fg(Color::Blue)),
+            );
+            frame.render_widget(paragraph, area);
+            return;
+        }
+
+        let chunks = Layout::vertical([Constraint::Length(3), Constraint::Length(2)]).split(area);
+        frame.render_widget(&self.file, chunks[0]);
+        let mut never_remove_style = Style::default().fg(Color::LightRed);
+        if self.focus == 1 {
+            never_remove_style = never_remove_style.add_modifier(Modifier::REVERSED);
+        }
+        let roster = match &self.roster {
+            Some(roster) => format!("{} members loaded", roster.len()),
+            None => "loading members...".to_string(),
+        };
+        let text = vec![
+            Line::from(vec![
+                Span::styled(format!("{} Never remove members", if self.never_remove { "[x]" } else { "[ ]" }), never_remove_style),
+                Span::raw(format!("  ({})", roster)),
+            ]),
+            match &self.error {
+                Some(error) => Line::styled(error.clone(), Style::default().fg(Color::Red)),
+                None => Line::raw("Tab: next field, Enter: dry run, Esc: cancel"),
+            },
+        ];
+        frame.render_widget(Paragraph::new(text), chunks[1]);
+    }
+
+    fn input(&mut self, input: Input) -> PopupStatus {
+        let mut status = PopupStatus::Continue;
+        if let Some(plan) = &self.plan {
+            match input {
+                Input { key: Key::Esc, .. } |
+                Input { key: Key::Char('n'), .. } => self.plan = None,
+                Input { key: Key::Enter, .. } |
+                Input { key: Key::Char('y'), .. } if !plan.is_empty() => status = PopupStatus::Submit,
+                Input { key: Key::Down, .. } => self.scroll = self.scroll.saturating_add(1),
+                Input { key: Key::Up, .. } => self.scroll = self.scroll.saturating_sub(1),
+                _input => {}
+            }
+            return status;
+        }
+        match input {
+            Input { key: Key::Esc, .. } => status = PopupStatus::Cancel,
+            Input { key: Key::Enter, .. } => self.plan(),
+            Input { key: Key::Tab, .. } => self.set_focus((self.focus + 1) % 2),
+            input => match self.focus {
+                0 => { self.file.input(input); }
+                _ => if let Input { key: Key::Left | Key::Right | Key::Char(' '), .. } = input {
+                    self.never_remove = !self.never_remove;
+                },
+            }
+        }
+
+        status
+    }
+
+    fn paste(&mut self, text: &str) {
+        if self.plan.is_none() && self.focus == 0 {
+            popup::paste_line(&mut self.file, text);
+        }
+    }
+
+    fn submit_all(&self) -> Vec<PopupReqParam> {
+        self.plan.as_ref().map_or(Vec::new(), |plan| plan.requests(&self.config, &self.config.list().unwrap().list_id()))
+    }
+
+    fn batch(&self) -> bool {
+        true
+    }
+
+    // Only the removals; members the sync added are left alone
+    fn undo(&self) -> Option<Undo> {
+        let removals = self.plan.as_ref()?.removals();
+        if removals.is_empty() {
+            return None;
+        }
+        let params = removals.iter().flat_map(|member| undo::resubscribe(&self.config, member, None)).collect();
+        Some(Undo::Restore(format!("removing {} members in a sync (without their preferences)", removals.len()), params))
+    }
+
+    fn load(&self) -> Vec<PopupReqParam> {
+        let url = Url::parse(&format!("{}://{}:{}/3.1/lists/{}/roster/member",
+            self.config.protocol(),
+            self.config.host(),
+            self.config.port(),
+            self.config.list().unwrap().list_id())).unwrap();
+
+        vec![PopupReqParam::new_for(Method::GET, url, HashMap::new(), ResponseType::PopupData("roster".to_string()))]
+    }
+
+    fn loaded(&mut self, response: Response) {
+        if !response.is_success() {
+            self.error = Some(format!("Can't load the members: {}", response.status()));
+            return;
+        }
+        match serde_json::from_str::<Members>(&response.text()) {
+            Ok(members) => self.roster = Some(members.entries().unwrap_or_default()),
+            Err(e) => self.error = Some(format!("Can't read the members: {}", e)),
+        }
+    }
+}
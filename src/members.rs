@@ -27,12 +27,16 @@ pub struct Entry {
 }
 
 impl Members {
-    pub fn list_vec(&self) -> Vec<String> {
-        if let Some(entries) = &self.entries {
-            entries.iter().map(|entry| entry.email().clone()).collect()
+    pub fn table(&self, columns: &[String]) -> (Vec<String>, Vec<Vec<String>>) {
+        let columns: Vec<String> = columns.iter().filter(|c| Entry::COLUMNS.contains(&c.as_str())).cloned().collect();
+        let rows = if let Some(entries) = &self.entries {
+            entries.iter().map(|entry| {
+                columns.iter().map(|column| entry.column(column).unwrap_or_default()).collect()
+            }).collect()
         } else {
             Vec::new()
-        }
+        };
+        (columns, rows)
     }
 
     pub fn entries(&self) -> Option<Vec<Entry>> {
@@ -41,6 +45,35 @@ impl Members {
 }
 
 impl Entry {
+    pub const COLUMNS: [&'static str; 10] = [
+        "email",
+        "display_name",
+        "role",
+        "delivery_mode",
+        "subscription_mode",
+        "list_id",
+        "bounce_score",
+        "total_warnings_sent",
+        "last_warning_sent",
+        "member_id",
+    ];
+
+    pub fn column(&self, column: &str) -> Option<String> {
+        match column {
+            "email" => Some(self.email()),
+            "display_name" => Some(self.display_name.clone()),
+            "role" => Some(self.role.clone()),
+            "delivery_mode" => Some(self.delivery_mode.clone()),
+            "subscription_mode" => Some(self.subscription_mode.clone()),
+            "list_id" => Some(self.list_id.clone()),
+            "bounce_score" => Some(self.bounce_score.to_string()),
+            "total_warnings_sent" => Some(self.total_warnings_sent.to_string()),
+            "last_warning_sent" => Some(self.last_warning_sent.clone()),
+            "member_id" => Some(self.member_id.clone()),
+            _ => None,
+        }
+    }
+
     pub fn email(&self) -> String {
         self.email.clone()
     }
@@ -48,4 +81,37 @@ impl Entry {
     pub fn member_id(&self) -> String {
         self.member_id.clone()
     }
+
+    pub fn list_id(&self) -> String {
+        self.list_id.clone()
+    }
+
+    pub fn display_name(&self) -> String {
+        self.display_name.clone()
+    }
+
+    pub fn delivery_mode(&self) -> String {
+        self.delivery_mode.clone()
+    }
+
+    pub fn role(&self) -> String {
+        self.role.clone()
+    }
+
+    pub fn bounce_score(&self) -> u32 {
+        self.bounce_score
+    }
+
+    pub fn total_warnings_sent(&self) -> u32 {
+        self.total_warnings_sent
+    }
+
+    // Mailman reports datetime.min for members that were never warned
+    pub fn last_warning(&self) -> String {
+        if self.last_warning_sent.starts_with("0001-") {
+            "(never)".to_string()
+        } else {
+            self.last_warning_sent.clone()
+        }
+    }
 }
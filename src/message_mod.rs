@@ -2,35 +2,52 @@ use std::collections::HashMap;
 
 use ratatui::{prelude::*, widgets::*};
 use reqwest::{Method, Url};
+use serde_json::{Map, value::Value};
 use tui_textarea::{Input, Key};
 
-use crate::{config::Config, popup::{Popup, PopupReqParam, PopupStatus}};
+use crate::{alias_edit, config::Config, popup::{self, Buttons, Popup, PopupReqParam, PopupStatus}, response::{Response, ResponseType}, undo::Undo};
 
 #[derive(Clone)]
 enum ModAction {
     Discard,
     Reject,
     Defer,
-    Accept
+    Accept,
+    AddAlias,
 }
 
 #[derive(Clone)]
 pub struct MessageMod<'a> {
     config: Config,
     paragraph: Paragraph<'a>,
+    buttons: Buttons,
     action: ModAction,
+    alias: Option<String>,
+    aliases: Option<Vec<String>>,
+    hint: Option<String>,
 }
 
 impl<'a> MessageMod<'a> {
     pub fn new(config: Config) -> Self {
-        let text = vec![
-            Line::from(config.message().unwrap().description()),
+        let message = config.message().unwrap();
+        let mut text = vec![
+            Line::from(message.description()),
+            Line::from(format!("Reason: {}", message.reason())),
             Line::from(""),
             Line::from("Type 'd' to discard the message"),
             Line::from("Type 'r' to reject the message"),
             Line::from("Type Esc to defer"),
             Line::from("Type 'a' or Enter to accept the message")
         ];
+        // The message was sent to an address the list doesn't know as its own, e.g. a forwarding address
+        let mut alias = None;
+        if message.implicit_destination() {
+            let posting_address = config.list().unwrap().fqdn_listname().to_lowercase();
+            alias = message.recipients().into_iter().find(|address| *address != posting_address);
+            if let Some(alias) = &alias {
+                text.push(Line::from(format!("Type 'i' to add {} as acceptable alias", alias)));
+            }
+        }
         let paragraph = Paragraph::new(text)
             .block(Block::default()
             .borders(Borders::ALL)
@@ -38,26 +55,49 @@ impl<'a> MessageMod<'a> {
             .title(" Message Moderation ".to_string())
             .style(Style::default().fg(Color::Blue)),
         );
+        let mut buttons = vec![("Accept", Key::Enter), ("Reject", Key::Char('r')), ("Discard", Key::Char('d')), ("Defer", Key::Esc)];
+        if alias.is_some() {
+            buttons.push(("Add alias", Key::Char('i')));
+        }
+        let buttons = Buttons::new(&buttons);
         let action = ModAction::Defer;
+        let aliases = None;
 
         Self {
             config,
             paragraph,
+            buttons,
             action,
+            alias,
+            aliases,
+            hint: None,
         }
     }
+
+    // Replaces the list's acceptable aliases
+    fn set_aliases(&self, aliases: Vec<String>) -> PopupReqParam {
+        let url = Url::parse(&format!("{}://{}:{}/3.1/lists/{}/config",
+            self.config.protocol(),
+            self.config.host(),
+            self.config.port(),
+            self.config.list().unwrap().list_id())).unwrap();
+        let mut map = Map::new();
+        map.insert("acceptable_aliases".to_string(), Value::from(aliases));
+
+        PopupReqParam::new_json(Method::PATCH, url, map)
+    }
 }
 
 impl Popup for MessageMod<'_> {
     fn render(&mut self, frame: &mut Frame) {
-        let area = Rect {
-            width: 80,
-            height: 8,
-            x: 42,
-            y: 20,
-        };
+        let area = popup::area(frame, 80, if self.hint.is_some() { 12 } else { 11 });
 
         frame.render_widget(self.paragraph.clone(), area);
+        if let Some(hint) = &self.hint {
+            let line = Rect::new(area.x + 1, (area.y + area.height).saturating_sub(3), area.width.saturating_sub(2), 1).intersection(area);
+            frame.render_widget(Paragraph::new(Line::styled(hint.clone(), Style::default().fg(Color::Red))), line);
+        }
+        self.buttons.render(frame, area);
     }
 
     fn input(&mut self, input: Input) -> PopupStatus {
@@ -74,6 +114,16 @@ impl Popup for MessageMod<'_> {
                 status = PopupStatus::Submit
             },
             Input { key: Key::Esc, .. } => status = PopupStatus::Cancel,
+            // Wait for the current aliases, otherwise we'd replace them
+            Input { key: Key::Char('i'), .. } |
+            Input { key: Key::Char('I'), .. } if self.alias.is_some() => {
+                if self.aliases.is_some() {
+                    self.action = ModAction::AddAlias;
+                    status = PopupStatus::Submit
+                } else if self.hint.is_none() {
+                    self.hint = Some("Still loading the acceptable aliases, try again in a moment".to_string());
+                }
+            },
             Input { key: Key::Enter, .. } |
             Input { key: Key::Char('a'), .. } |
             Input { key: Key::Char('A'), .. } => {
@@ -86,7 +136,22 @@ impl Popup for MessageMod<'_> {
         status
     }
 
-    fn submit(&self) -> PopupReqParam {
+    fn buttons(&self) -> Option<&Buttons> {
+        Some(&self.buttons)
+    }
+
+    fn submit(&self) -> Option<PopupReqParam> {
+        let action = match self.action {
+            ModAction::Discard => "discard",
+            ModAction::Reject => "reject",
+            ModAction::Defer => "defer",
+            ModAction::Accept => "accept",
+            ModAction::AddAlias => {
+                let mut aliases = self.aliases.clone().unwrap_or_default();
+                aliases.extend(self.alias.clone());
+                return Some(self.set_aliases(aliases));
+            }
+        };
         let url = Url::parse(&format!("{}://{}:{}/3.1/lists/{}/held/{}",
             self.config.protocol(),
             self.config.host(),
@@ -94,13 +159,47 @@ impl Popup for MessageMod<'_> {
             self.config.list().unwrap().fqdn_listname(),
             self.config.message().unwrap().request_id())).unwrap();
         let mut map = HashMap::new();
-        match self.action {
-            ModAction::Discard => map.insert("action".to_string(), "discard".to_string()),
-            ModAction::Reject => map.insert("action".to_string(), "reject".to_string()),
-            ModAction::Defer => map.insert("action".to_string(), "defer".to_string()),
-            ModAction::Accept => map.insert("action".to_string(), "accept".to_string())
-        };
+        map.insert("action".to_string(), action.to_string());
+
+        Some(PopupReqParam::new(Method::POST, url, map))
+    }
+
+    fn load(&self) -> Vec<PopupReqParam> {
+        if self.alias.is_none() {
+            return Vec::new();
+        }
+        let url = Url::parse(&format!("{}://{}:{}/3.1/lists/{}/config/acceptable_aliases",
+            self.config.protocol(),
+            self.config.host(),
+            self.config.port(),
+            self.config.list().unwrap().list_id())).unwrap();
+
+        vec![PopupReqParam::new_for(Method::GET, url, HashMap::new(), ResponseType::PopupData("acceptable_aliases".to_string()))]
+    }
 
-        PopupReqParam::new(Method::POST, url, map)
+    fn loaded(&mut self, response: Response) {
+        if response.is_success() {
+            self.aliases = Some(alias_edit::acceptable_aliases(&response.text()));
+            self.hint = None;
+        } else {
+            self.hint = Some(format!("Can't load the acceptable aliases: {}", response.status()));
+        }
+    }
+
+    // Once a message was handed on or thrown away Mailman forgets it
+    fn undo(&self) -> Option<Undo> {
+        let verb = match self.action {
+            ModAction::Discard => "discarding",
+            ModAction::Reject => "rejecting",
+            ModAction::Accept => "accepting",
+            // Adding the alias leaves the message held; the aliases it replaced are known
+            ModAction::AddAlias => {
+                let description = format!("adding {} as acceptable alias", self.alias.clone().unwrap_or_default());
+                let aliases = self.aliases.clone().unwrap_or_default();
+                return Some(Undo::Restore(description, vec![self.set_aliases(aliases)]));
+            }
+            ModAction::Defer => return None,
+        };
+        Some(Undo::Impossible(format!("{} \"{}\"", verb, self.config.message().unwrap().description())))
     }
 }
\ No newline at end of file
@@ -46,4 +46,48 @@ impl Entry {
     pub fn request_id(&self) -> u32 {
         self.request_id
     }
+
+    pub fn reason(&self) -> String {
+        self.reason.clone()
+    }
+
+    pub fn implicit_destination(&self) -> bool {
+        self.reason.to_lowercase().contains("implicit destination")
+    }
+
+    // Addresses from the To and Cc headers of the held message
+    pub fn recipients(&self) -> Vec<String> {
+        let mut headers: Vec<String> = Vec::new();
+        for line in self.msg.lines() {
+            if line.trim().is_empty() {
+                break;
+            }
+            if line.starts_with(' ') || line.starts_with('\t') {
+                if let Some(last) = headers.last_mut() {
+                    last.push(' ');
+                    last.push_str(line.trim());
+                }
+            } else {
+                headers.push(line.to_string());
+            }
+        }
+
+        headers.iter()
+            .filter_map(|header| header.split_once(':'))
+            .filter(|(name, _)| name.eq_ignore_ascii_case("to") || name.eq_ignore_ascii_case("cc"))
+            .flat_map(|(_, value)| value.split(','))
+            .filter_map(|address| {
+                let address = match (address.find('<'), address.find('>')) {
+                    (Some(start), Some(end)) if start < end => &address[start + 1..end],
+                    _ => address,
+                };
+                let address = address.trim();
+                if address.contains('@') {
+                    Some(address.to_lowercase())
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
 }
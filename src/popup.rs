@@ -1,30 +1,94 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, path::PathBuf};
 
 use reqwest::{Method, Url};
-use ratatui::prelude::*;
-use tui_textarea::Input;
+use ratatui::{prelude::*, widgets::*};
+use serde_json::{Map, value::Value};
+use tui_textarea::{Input, Key, TextArea};
+
+use crate::{response::{Response, ResponseType}, undo::Undo, Action};
 
 pub enum PopupStatus {
     Continue,
     Cancel,
     Submit,
+    // Closes the popup and hands the action to Marge, e.g. to open another view
+    Action(Action),
+    // Suspends the TUI to edit the file in $EDITOR; the popup stays open
+    Edit(PathBuf),
+    // Sends the popup's load() requests again, e.g. after a different target list was chosen
+    Load,
 }
 
+#[derive(Clone)]
 pub struct PopupReqParam {
     method : Method,
     url: Url,
-    map: HashMap<String, String>,
+    map: Map<String, Value>,
+    response_type: ResponseType,
+    label: Option<String>,
+    needs_previous: bool,
+    required: bool,
 }
 
 impl PopupReqParam {
     pub fn new(method: Method, url: Url, map: HashMap<String, String>) -> Self {
+        Self::new_for(method, url, map, ResponseType::Popup)
+    }
+
+    // For popups whose response should be shown in one of the views instead of just the status bar
+    pub fn new_for(method: Method, url: Url, map: HashMap<String, String>, response_type: ResponseType) -> Self {
+        Self {
+            method,
+            url,
+            map: map.into_iter().map(|(key, value)| (key, Value::String(value))).collect(),
+            response_type,
+            label: None,
+            needs_previous: false,
+            required: false,
+        }
+    }
+
+    // For values Mailman doesn't accept as strings, e.g. lists like acceptable_aliases
+    pub fn new_json(method: Method, url: Url, map: Map<String, Value>) -> Self {
         Self {
             method,
             url,
             map,
+            response_type: ResponseType::Popup,
+            label: None,
+            needs_previous: false,
+            required: false,
         }
     }
 
+    // Names the request in batch reports, e.g. with the address a DELETE unsubscribes
+    pub fn with_label(mut self, label: String) -> Self {
+        self.label = Some(label);
+        self
+    }
+
+    // In a batch, skips the request if the one before it failed or was skipped,
+    // e.g. unsubscribing from the old list after subscribing to the new one
+    pub fn if_previous_succeeded(mut self) -> Self {
+        self.needs_previous = true;
+        self
+    }
+
+    pub fn needs_previous(&self) -> bool {
+        self.needs_previous
+    }
+
+    // In a batch, skips everything after the request if it fails,
+    // e.g. unsubscribing members after switching a setting they depend on
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    pub fn is_required(&self) -> bool {
+        self.required
+    }
+
     pub fn method(&self) -> Method {
         self.method.clone()
     }
@@ -33,13 +97,137 @@ impl PopupReqParam {
         self.url.clone()
     }
 
-    pub fn map(&self) -> HashMap<String, String> {
+    pub fn map(&self) -> Map<String, Value> {
         self.map.clone()
     }
+
+    pub fn response_type(&self) -> ResponseType {
+        self.response_type.clone()
+    }
+
+    pub fn label(&self) -> String {
+        self.label.clone().unwrap_or(format!("{} {}", self.method, self.url.path()))
+    }
+}
+
+// Where a popup of the given size goes: centered on the screen and shrunk to fit it,
+// with whatever is underneath cleared, so the table doesn't shine through
+pub fn area(frame: &mut Frame, width: u16, height: u16) -> Rect {
+    let screen = frame.area();
+    let width = width.min(screen.width);
+    let height = height.min(screen.height);
+    let area = Rect {
+        x: screen.x + (screen.width - width) / 2,
+        y: screen.y + (screen.height - height) / 2,
+        width,
+        height,
+    };
+    frame.render_widget(Clear, area);
+    area
+}
+
+// A row of buttons for mouse users; each stands for a key the popup already understands
+#[derive(Clone)]
+pub struct Buttons {
+    buttons: Vec<(String, Key)>,
+    areas: Vec<Rect>,
+}
+
+impl Buttons {
+    pub fn new(buttons: &[(&str, Key)]) -> Self {
+        Self {
+            buttons: buttons.iter().map(|(label, key)| (label.to_string(), *key)).collect(),
+            areas: Vec::new(),
+        }
+    }
+
+    // Renders the buttons into the last line inside the popup's border
+    // and remembers where they went for hit()
+    pub fn render(&mut self, frame: &mut Frame, popup: Rect) {
+        let line = Rect {
+            x: popup.x + 2,
+            y: (popup.y + popup.height).saturating_sub(2),
+            width: popup.width.saturating_sub(4),
+            height: 1,
+        };
+        self.areas.clear();
+        let mut x = line.x;
+        for (label, _) in &self.buttons {
+            let text = format!("[{}]", label);
+            let width = (text.chars().count() as u16).min((line.x + line.width).saturating_sub(x));
+            let area = Rect { x, y: line.y, width, height: 1 };
+            frame.render_widget(Paragraph::new(text).style(Style::default().fg(Color::Black).bg(Color::LightBlue)), area);
+            self.areas.push(area);
+            x = (x + width + 1).min(line.x + line.width);
+        }
+    }
+
+    pub fn hit(&self, column: u16, row: u16) -> Option<Key> {
+        let position = Position { x: column, y: row };
+        self.areas.iter().position(|area| area.contains(position)).map(|i| self.buttons[i].1)
+    }
+}
+
+// Pastes into a one-line field. Line breaks are joined with spaces, as typing them
+// would be taken for Enter and submit the popup halfway through the paste.
+pub fn paste_line(text_area: &mut TextArea, text: &str) {
+    let text: Vec<&str> = text.lines().map(str::trim).filter(|line| !line.is_empty()).collect();
+    text_area.insert_str(text.join(" "));
 }
 
 pub trait Popup {
     fn render(&mut self, frame: &mut Frame);
     fn input(&mut self, input: Input) -> PopupStatus;
-    fn submit(&self) -> PopupReqParam;
+
+    // The request a popup sends when it returns PopupStatus::Submit. Popups only showing
+    // something never submit, those sending several requests override submit_all() instead.
+    fn submit(&self) -> Option<PopupReqParam> {
+        None
+    }
+
+    // Popups needing several requests (e.g. create a list, then configure it) override this.
+    // The requests are sent in order and sending stops at the first failure.
+    fn submit_all(&self) -> Vec<PopupReqParam> {
+        self.submit().into_iter().collect()
+    }
+
+    // Batches of independent requests, e.g. one DELETE per member, keep going after a failure
+    // and are summed up in the status bar instead of showing each response
+    fn batch(&self) -> bool {
+        false
+    }
+
+    // Added to the batch summary, e.g. what was left out before sending
+    fn batch_note(&self) -> Option<String> {
+        None
+    }
+
+    // Requests for data the popup needs from the server, e.g. the available list styles.
+    // Their responses must be of ResponseType::PopupData and are handed to loaded().
+    fn load(&self) -> Vec<PopupReqParam> {
+        Vec::new()
+    }
+
+    fn loaded(&mut self, _response: Response) {}
+
+    // Bracketed paste, i.e. the pasted text in one piece; popups put it into the focused field
+    fn paste(&mut self, _text: &str) {}
+
+    // Popups with buttons hand them out, so clicking one works like typing its key
+    fn buttons(&self) -> Option<&Buttons> {
+        None
+    }
+
+    fn click(&mut self, column: u16, row: u16) -> PopupStatus {
+        match self.buttons().and_then(|buttons| buttons.hit(column, row)) {
+            Some(key) => self.input(Input { key, ..Default::default() }),
+            None => PopupStatus::Continue,
+        }
+    }
+
+    // Destructive popups describe how to take back what submit_all() does.
+    // It's offered with 'U' once the requests went through.
+    fn undo(&self) -> Option<Undo> {
+        None
+    }
 }
\ No newline at end of file
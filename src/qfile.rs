@@ -0,0 +1,503 @@
+use std::{collections::HashMap, fs, path::Path};
+
+// A queue file holds two Python pickles, the message and its metadata, see
+// mailman/core/switchboard.py. The REST API only names the files, so they're read from the
+// queue directory when marge runs on the Mailman host and may read it, like `mailman qfile`.
+#[derive(Debug, Clone, Default)]
+pub struct QueuedMessage {
+    headers: Vec<(String, String)>,
+    body: Vec<String>,
+    text: String,
+    metadata: Vec<(String, String)>,
+}
+
+// Just enough of Python's pickle format for what Mailman puts into queue files
+#[derive(Debug, Clone)]
+enum Value {
+    None,
+    Mark,
+    Int(i64),
+    Float(f64),
+    Str(String),
+    Bytes(Vec<u8>),
+    List(Vec<Value>),
+    Tuple(Vec<Value>),
+    Dict(Vec<(Value, Value)>),
+    Global(String),
+    // An instance with the state BUILD gave it
+    Object(Box<Value>),
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], String> {
+        let end = self.pos.checked_add(n).filter(|end| *end <= self.data.len()).ok_or("the file ends early")?;
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn uint(&mut self, n: usize) -> Result<usize, String> {
+        Ok(self.take(n)?.iter().rev().fold(0, |value, byte| value << 8 | *byte as usize))
+    }
+
+    fn raw_line(&mut self) -> Result<&'a [u8], String> {
+        let len = self.data[self.pos..].iter().position(|byte| *byte == b'\n').ok_or("the file ends early")?;
+        let line = self.take(len)?;
+        self.pos += 1;
+        Ok(line)
+    }
+
+    fn line(&mut self) -> Result<String, String> {
+        Ok(String::from_utf8_lossy(self.raw_line()?).to_string())
+    }
+
+    fn string(&mut self, n: usize) -> Result<Value, String> {
+        Ok(Value::Str(String::from_utf8_lossy(self.take(n)?).to_string()))
+    }
+
+    fn bytes(&mut self, n: usize) -> Result<Value, String> {
+        Ok(Value::Bytes(self.take(n)?.to_vec()))
+    }
+
+    // One pickle, up to its STOP
+    fn load(&mut self) -> Result<Value, String> {
+        let mut stack: Vec<Value> = Vec::new();
+        let mut memo: HashMap<usize, Value> = HashMap::new();
+        loop {
+            let op = self.take(1)?[0];
+            let value = match op {
+                b'.' => return stack.pop().ok_or("empty pickle".to_string()),
+                b'\x80' => { self.take(1)?; continue }
+                b'\x95' => { self.take(8)?; continue }
+                b'(' => Value::Mark,
+                b'N' => Value::None,
+                b')' => Value::Tuple(Vec::new()),
+                b']' | b'\x8f' => Value::List(Vec::new()),
+                b'}' => Value::Dict(Vec::new()),
+                b'\x88' => Value::Int(1),
+                b'\x89' => Value::Int(0),
+                b'K' => Value::Int(self.uint(1)? as i64),
+                b'M' => Value::Int(self.uint(2)? as i64),
+                b'J' => Value::Int(self.uint(4)? as u32 as i32 as i64),
+                b'I' | b'L' => {
+                    let line = self.line()?;
+                    Value::Int(line.trim_end_matches('L').parse().unwrap_or_default())
+                }
+                b'\x8a' | b'\x8b' => {
+                    let n = if op == b'\x8a' { self.uint(1)? } else { self.uint(4)? };
+                    let bytes = self.take(n)?;
+                    // Longs that don't fit are only ever timestamps and counters nobody reads here
+                    let value = bytes.iter().rev().take(8).fold(0i64, |value, byte| value << 8 | *byte as i64);
+                    Value::Int(value)
+                }
+                b'F' => Value::Float(self.line()?.parse().unwrap_or_default()),
+                b'G' => Value::Float(f64::from_be_bytes(self.take(8)?.try_into().unwrap())),
+                b'X' | b'T' => { let n = self.uint(4)?; self.string(n)? }
+                b'\x8c' | b'U' => { let n = self.uint(1)?; self.string(n)? }
+                b'\x8d' => { let n = self.uint(8)?; self.string(n)? }
+                // raw-unicode-escape: Latin-1 with \uXXXX escapes for the rest
+                b'V' => Value::Str(unescape(&self.raw_line()?.iter().map(|byte| *byte as char).collect::<String>())),
+                b'S' => Value::Str(unescape(self.line()?.trim_matches(|c| c == '\'' || c == '"'))),
+                b'C' => { let n = self.uint(1)?; self.bytes(n)? }
+                b'B' => { let n = self.uint(4)?; self.bytes(n)? }
+                b'\x8e' | b'\x96' => { let n = self.uint(8)?; self.bytes(n)? }
+                b't' => Value::Tuple(pop_mark(&mut stack)?),
+                b'\x85' | b'\x86' | b'\x87' => {
+                    let n = (op - b'\x84') as usize;
+                    let items = stack.split_off(stack.len().checked_sub(n).ok_or("broken tuple")?);
+                    Value::Tuple(items)
+                }
+                b'l' | b'\x91' => Value::List(pop_mark(&mut stack)?),
+                b'd' => Value::Dict(pairs(pop_mark(&mut stack)?)),
+                b'a' => {
+                    let item = pop(&mut stack)?;
+                    if let Some(Value::List(items)) = stack.last_mut() {
+                        items.push(item);
+                    }
+                    continue
+                }
+                b'e' | b'\x90' => {
+                    let new = pop_mark(&mut stack)?;
+                    if let Some(Value::List(items)) = stack.last_mut() {
+                        items.extend(new);
+                    }
+                    continue
+                }
+                b's' | b'u' => {
+                    let new = if op == b's' {
+                        let value = pop(&mut stack)?;
+                        vec![(pop(&mut stack)?, value)]
+                    } else {
+                        pairs(pop_mark(&mut stack)?)
+                    };
+                    if let Some(Value::Dict(items)) = stack.last_mut() {
+                        items.extend(new);
+                    }
+                    continue
+                }
+                // The memo keeps copies, which is all reading needs
+                b'p' | b'q' | b'r' | b'\x94' => {
+                    let index = match op {
+                        b'p' => self.line()?.parse().map_err(|_| "broken memo index")?,
+                        b'q' => self.uint(1)?,
+                        b'r' => self.uint(4)?,
+                        _ => memo.len(),
+                    };
+                    memo.insert(index, stack.last().cloned().ok_or("nothing to memoize")?);
+                    continue
+                }
+                b'g' | b'h' | b'j' => {
+                    let index = match op {
+                        b'g' => self.line()?.parse().map_err(|_| "broken memo index")?,
+                        b'h' => self.uint(1)?,
+                        _ => self.uint(4)?,
+                    };
+                    memo.get(&index).cloned().ok_or("unknown memo index")?
+                }
+                b'c' => Value::Global(format!("{}.{}", self.line()?, self.line()?)),
+                b'\x93' => {
+                    let name = pop(&mut stack)?;
+                    let module = pop(&mut stack)?;
+                    Value::Global(format!("{}.{}", text(&module), text(&name)))
+                }
+                b'i' => {
+                    // Module and class name
+                    self.line()?;
+                    self.line()?;
+                    pop_mark(&mut stack)?;
+                    Value::Object(Box::new(Value::None))
+                }
+                b'o' => {
+                    pop_mark(&mut stack)?;
+                    Value::Object(Box::new(Value::None))
+                }
+                b'R' | b'\x81' => {
+                    pop(&mut stack)?;
+                    pop(&mut stack)?;
+                    Value::Object(Box::new(Value::None))
+                }
+                b'\x92' => {
+                    pop(&mut stack)?;
+                    pop(&mut stack)?;
+                    pop(&mut stack)?;
+                    Value::Object(Box::new(Value::None))
+                }
+                b'b' => {
+                    let state = pop(&mut stack)?;
+                    if let Some(Value::Object(object)) = stack.last_mut() {
+                        **object = state;
+                    }
+                    continue
+                }
+                b'0' => { pop(&mut stack)?; continue }
+                b'1' => { pop_mark(&mut stack)?; continue }
+                b'2' => stack.last().cloned().ok_or("nothing to duplicate")?,
+                _ => return Err(format!("unsupported pickle opcode {:#04x}", op)),
+            };
+            stack.push(value);
+        }
+    }
+}
+
+fn pop(stack: &mut Vec<Value>) -> Result<Value, String> {
+    stack.pop().ok_or("the pickle's stack runs empty".to_string())
+}
+
+fn pop_mark(stack: &mut Vec<Value>) -> Result<Vec<Value>, String> {
+    let mark = stack.iter().rposition(|value| matches!(value, Value::Mark)).ok_or("missing mark")?;
+    let items = stack.split_off(mark + 1);
+    stack.pop();
+    Ok(items)
+}
+
+fn pairs(items: Vec<Value>) -> Vec<(Value, Value)> {
+    let mut items = items.into_iter();
+    let mut pairs = Vec::new();
+    while let (Some(key), Some(value)) = (items.next(), items.next()) {
+        pairs.push((key, value));
+    }
+    pairs
+}
+
+// \uXXXX escapes of the text protocol, plus those of Python string literals
+fn unescape(line: &str) -> String {
+    let mut text = String::new();
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            text.push(c);
+            continue;
+        }
+        let hex = |chars: &mut std::str::Chars, n: usize| {
+            let digits: String = chars.take(n).collect();
+            u32::from_str_radix(&digits, 16).ok().and_then(char::from_u32).unwrap_or('\u{fffd}')
+        };
+        match chars.next() {
+            Some('n') => text.push('\n'),
+            Some('r') => text.push('\r'),
+            Some('t') => text.push('\t'),
+            Some('x') => text.push(hex(&mut chars, 2)),
+            Some('u') => text.push(hex(&mut chars, 4)),
+            Some('U') => text.push(hex(&mut chars, 8)),
+            Some(c) => text.push(c),
+            None => text.push('\\'),
+        }
+    }
+    text
+}
+
+fn get<'a>(dict: &'a Value, key: &str) -> Option<&'a Value> {
+    match dict {
+        Value::Dict(items) => items.iter().find(|(k, _)| matches!(k, Value::Str(k) if k == key)).map(|(_, v)| v),
+        Value::Object(state) => get(state, key),
+        _ => None,
+    }
+}
+
+// Header values are strings or email.header.Header objects, made of (text, charset) chunks
+fn text(value: &Value) -> String {
+    match value {
+        Value::Str(text) => text.clone(),
+        Value::Bytes(bytes) => String::from_utf8_lossy(bytes).to_string(),
+        Value::Int(n) => n.to_string(),
+        Value::Float(n) => n.to_string(),
+        Value::None => "None".to_string(),
+        Value::Object(_) => match get(value, "_chunks") {
+            Some(Value::List(chunks)) => chunks.iter().filter_map(|chunk| match chunk {
+                Value::Tuple(parts) => parts.first().map(text),
+                _ => None,
+            }).collect::<Vec<String>>().join(" "),
+            _ => "(object)".to_string(),
+        },
+        Value::Global(name) => name.clone(),
+        _ => "(...)".to_string(),
+    }
+}
+
+// What Mailman lets the runners parse itself comes as plain text
+fn parse(raw: &str) -> (Vec<(String, String)>, Vec<String>) {
+    let (head, body) = raw.split_once("\n\n").unwrap_or((raw, ""));
+    let mut headers: Vec<(String, String)> = Vec::new();
+    for line in head.lines() {
+        if line.starts_with([' ', '\t']) {
+            if let Some((_, value)) = headers.last_mut() {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+        } else if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.to_string(), value.trim().to_string()));
+        }
+    }
+    (headers, body.lines().map(str::to_string).collect())
+}
+
+// The lines of the first part with text in it
+fn body(message: &Value) -> Vec<String> {
+    match get(message, "_payload") {
+        Some(Value::Str(payload)) => payload.lines().map(str::to_string).collect(),
+        Some(Value::List(parts)) => {
+            let mut lines = vec![format!("(multipart message with {} parts, the first one follows)", parts.len())];
+            if let Some(part) = parts.first() {
+                lines.extend(get(part, "_headers").map(header_list).unwrap_or_default().into_iter()
+                    .map(|(name, value)| format!("{}: {}", name, value)));
+                lines.push(String::new());
+                lines.extend(body(part));
+            }
+            lines
+        }
+        _ => Vec::new(),
+    }
+}
+
+fn header_list(list: &Value) -> Vec<(String, String)> {
+    match list {
+        Value::List(items) => items.iter().filter_map(|item| match item {
+            // Folded values keep their line breaks
+            Value::Tuple(pair) if pair.len() == 2 => Some((text(&pair[0]), text(&pair[1]).lines().map(str::trim).collect::<Vec<&str>>().join(" "))),
+            _ => None,
+        }).collect(),
+        _ => Vec::new(),
+    }
+}
+
+// The message as the email generator writes it, all parts included, for injecting it again
+fn message_text(message: &Value) -> String {
+    let headers = get(message, "_headers").map(header_list).unwrap_or_default();
+    let mut text: String = headers.iter().map(|(name, value)| format!("{}: {}\n", name, value)).collect();
+    text.push('\n');
+    match get(message, "_payload") {
+        Some(Value::Str(payload)) => text.push_str(payload),
+        Some(Value::List(parts)) => {
+            let boundary = headers.iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case("Content-Type"))
+                .and_then(|(_, value)| value.split(';').find_map(|param| param.trim().strip_prefix("boundary=")))
+                .map(|boundary| boundary.trim_matches('"').to_string())
+                .unwrap_or_default();
+            if let Some(Value::Str(preamble)) = get(message, "preamble") {
+                text.push_str(preamble);
+                text.push('\n');
+            }
+            for part in parts {
+                text.push_str(&format!("--{}\n", boundary));
+                text.push_str(&message_text(part));
+                text.push('\n');
+            }
+            text.push_str(&format!("--{}--\n", boundary));
+            if let Some(Value::Str(epilogue)) = get(message, "epilogue") {
+                text.push_str(epilogue);
+            }
+        }
+        _ => {}
+    }
+    text
+}
+
+impl QueuedMessage {
+    pub fn read(path: &Path) -> Result<Self, String> {
+        let data = fs::read(path).map_err(|e| format!("Can't read {}: {}", path.display(), e))?;
+        Self::from_pickles(&data).map_err(|e| format!("Can't read {}: {}", path.display(), e))
+    }
+
+    fn from_pickles(data: &[u8]) -> Result<Self, String> {
+        let mut reader = Reader {
+            data,
+            pos: 0,
+        };
+        let message = reader.load()?;
+        // Old queue files may lack the metadata; the message is what matters
+        let metadata = reader.load().unwrap_or(Value::Dict(Vec::new()));
+
+        let (headers, body, raw) = match &message {
+            Value::Str(_) | Value::Bytes(_) => {
+                let raw = text(&message);
+                let (headers, body) = parse(&raw);
+                (headers, body, raw)
+            }
+            Value::Object(_) => (get(&message, "_headers").map(header_list).unwrap_or_default(), body(&message), message_text(&message)),
+            _ => return Err("it holds no message".to_string()),
+        };
+        let metadata = match metadata {
+            Value::Dict(items) => items.iter()
+                .filter(|(_, value)| matches!(value, Value::Str(_) | Value::Int(_) | Value::Float(_) | Value::None))
+                .map(|(key, value)| (text(key), text(value)))
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        Ok(Self {
+            headers,
+            body,
+            text: raw,
+            metadata,
+        })
+    }
+
+    pub fn header(&self, name: &str) -> Option<String> {
+        self.headers.iter().find(|(n, _)| n.eq_ignore_ascii_case(name)).map(|(_, value)| value.clone())
+    }
+
+    pub fn headers(&self) -> Vec<(String, String)> {
+        self.headers.clone()
+    }
+
+    pub fn body(&self) -> Vec<String> {
+        self.body.clone()
+    }
+
+    pub fn text(&self) -> String {
+        self.text.clone()
+    }
+
+    pub fn metadata(&self) -> Vec<(String, String)> {
+        self.metadata.clone()
+    }
+
+    pub fn metadata_value(&self, key: &str) -> Option<String> {
+        self.metadata.iter().find(|(k, _)| k == key).map(|(_, value)| value.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::QueuedMessage;
+
+    // BINUNICODE
+    fn string(text: &str) -> Vec<u8> {
+        let mut bytes = vec![b'X'];
+        bytes.extend((text.len() as u32).to_le_bytes());
+        bytes.extend(text.as_bytes());
+        bytes
+    }
+
+    fn metadata() -> Vec<u8> {
+        let mut data = b"}q\x00(".to_vec();
+        data.extend(string("whichq"));
+        data.extend(string("in"));
+        data.extend(string("version"));
+        data.extend(b"K\x03u.");
+        data
+    }
+
+    #[test]
+    fn message_object() {
+        // What protocol 1 makes of an email.message.Message, with a memoized header name
+        let mut data = b"ccopy_reg\n_reconstructor\nq\x00(cemail.message\nMessage\nc__builtin__\nobject\nNtR}q\x01(".to_vec();
+        data.extend(string("_headers"));
+        data.extend(b"]((");
+        data.extend(string("Received"));
+        data.extend(b"q\x02");
+        data.extend(string("from a"));
+        data.push(b't');
+        data.push(b'(');
+        data.extend(b"h\x02");
+        data.extend(string("from b\n\tby c"));
+        data.push(b't');
+        data.push(b'(');
+        data.extend(string("Subject"));
+        data.extend(string("Hi"));
+        data.extend(b"te");
+        data.extend(string("_payload"));
+        data.extend(string("line 1\nline 2\n"));
+        data.extend(b"ub.");
+        data.extend(metadata());
+
+        let message = QueuedMessage::from_pickles(&data).unwrap();
+        assert_eq!(message.headers(), vec![
+            ("Received".to_string(), "from a".to_string()),
+            ("Received".to_string(), "from b by c".to_string()),
+            ("Subject".to_string(), "Hi".to_string()),
+        ]);
+        assert_eq!(message.header("subject"), Some("Hi".to_string()));
+        assert_eq!(message.body(), vec!["line 1", "line 2"]);
+        assert_eq!(message.text(), "Received: from a\nReceived: from b by c\nSubject: Hi\n\nline 1\nline 2\n");
+        assert_eq!(message.metadata_value("whichq"), Some("in".to_string()));
+        assert_eq!(message.metadata(), vec![
+            ("whichq".to_string(), "in".to_string()),
+            ("version".to_string(), "3".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn message_text() {
+        let mut data = string("From: a@example.com\nSubject: Long\n  subject\n\nbody\n");
+        data.push(b'.');
+        data.extend(metadata());
+
+        let message = QueuedMessage::from_pickles(&data).unwrap();
+        assert_eq!(message.header("From"), Some("a@example.com".to_string()));
+        assert_eq!(message.header("Subject"), Some("Long subject".to_string()));
+        assert_eq!(message.body(), vec!["body"]);
+    }
+
+    #[test]
+    fn broken() {
+        assert!(QueuedMessage::from_pickles(b"").is_err());
+        assert!(QueuedMessage::from_pickles(b"X\xff\x00\x00\x00abc").is_err());
+        assert!(QueuedMessage::from_pickles(b"K\x01.").is_err());
+    }
+}
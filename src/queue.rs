@@ -0,0 +1,104 @@
+use serde::{Serialize, Deserialize};
+
+use std::path::PathBuf;
+
+use crate::{qfile::QueuedMessage, timestamp};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Queue {
+    count: u32,
+    directory: String,
+    files: Vec<String>,
+    http_etag: String,
+    name: String,
+    self_link: String,
+    // From and Subject of each file, read off the UI thread after the listing came in
+    #[serde(skip)]
+    headers: Option<Vec<(String, String)>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Entry {
+    queue: String,
+    file: String,
+    directory: String,
+}
+
+impl Queue {
+    // From and Subject only show where marge may read the queue directory
+    pub fn table(&self) -> (Vec<String>, Vec<Vec<String>>) {
+        let entries = self.entries().unwrap_or_default();
+        let Some(headers) = &self.headers else {
+            let header = vec!["file".to_string(), "enqueued".to_string()];
+            let rows = entries.iter().map(|entry| vec![entry.file(), entry.enqueued()]).collect();
+            return (header, rows);
+        };
+
+        let header = ["file", "enqueued", "from", "subject"].iter().map(|c| c.to_string()).collect();
+        let rows = entries.iter().zip(headers).map(|(entry, (from, subject))| {
+            vec![entry.file(), entry.enqueued(), from.clone(), subject.clone()]
+        }).collect();
+        (header, rows)
+    }
+
+    // Parses every file, so it belongs in a blocking task. None if no file could be read.
+    pub fn read_headers(&self) -> Option<Vec<(String, String)>> {
+        let messages: Vec<Option<QueuedMessage>> = self.entries().unwrap_or_default().iter()
+            .map(|entry| entry.message().ok())
+            .collect();
+        if messages.iter().all(Option::is_none) {
+            return None;
+        }
+        Some(messages.iter().map(|message| {
+            let header = |name: &str| message.as_ref().and_then(|message| message.header(name)).unwrap_or_default();
+            (header("From"), header("Subject"))
+        }).collect())
+    }
+
+    pub fn set_headers(&mut self, headers: Vec<(String, String)>) {
+        self.headers = Some(headers);
+    }
+
+    // Whether both list the same files, so headers read for one fit the other
+    pub fn same_files(&self, other: &Queue) -> bool {
+        self.name == other.name && self.files == other.files
+    }
+
+    pub fn entries(&self) -> Option<Vec<Entry>> {
+        if self.files.is_empty() {
+            return None;
+        }
+        Some(self.files.iter().map(|file| Entry {
+            queue: self.name.clone(),
+            file: file.clone(),
+            directory: self.directory.clone(),
+        }).collect())
+    }
+}
+
+impl Entry {
+    pub fn queue(&self) -> String {
+        self.queue.clone()
+    }
+
+    pub fn file(&self) -> String {
+        self.file.clone()
+    }
+
+    pub fn path(&self) -> PathBuf {
+        PathBuf::from(&self.directory).join(format!("{}.pck", self.file))
+    }
+
+    pub fn message(&self) -> Result<QueuedMessage, String> {
+        QueuedMessage::read(&self.path())
+    }
+
+    // Mailman names queue files <time>+<hash>, with the time they were enqueued in seconds
+    pub fn enqueued(&self) -> String {
+        self.file
+            .split('+')
+            .next()
+            .and_then(|time| time.parse::<f64>().ok())
+            .map_or("(unknown)".to_string(), |time| timestamp::format_utc(time as u64))
+    }
+}
@@ -0,0 +1,107 @@
+use std::collections::HashMap;
+
+use ratatui::{prelude::*, widgets::*};
+use reqwest::{Method, Url};
+use tui_textarea::{Input, Key};
+
+use crate::{config::Config, popup::{self, Buttons, Popup, PopupReqParam, PopupStatus}, undo::Undo, Action};
+
+#[derive(Clone)]
+pub struct QueueFile {
+    config: Config,
+    text: Vec<String>,
+    scroll: u16,
+    buttons: Buttons,
+}
+
+impl QueueFile {
+    pub fn new(config: Config) -> Self {
+        let queue_file = config.queue_file().unwrap();
+        let path = queue_file.path();
+        let mut text = vec![
+            format!("Queue:    {}", queue_file.queue()),
+            format!("File:     {}", queue_file.file()),
+            format!("Enqueued: {}", queue_file.enqueued()),
+            format!("Path:     {}", path.display()),
+            String::new(),
+        ];
+        match queue_file.message() {
+            Ok(message) => {
+                // whichq is the queue a shunted message came from, the one to inject it back into
+                text.extend(message.metadata().into_iter().map(|(key, value)| format!("{}: {}", key, value)));
+                text.push(String::new());
+                text.extend(message.headers().into_iter().map(|(name, value)| format!("{}: {}", name, value)));
+                text.push(String::new());
+                text.extend(message.body());
+            }
+            // The REST API only names the file; its content is only there on the Mailman host
+            Err(e) => {
+                text.push(e);
+                text.push(String::new());
+                text.push("Run marge on the Mailman host as a user who may read the queue".to_string());
+                text.push(format!("directory, or run there: mailman qfile {}", path.display()));
+            }
+        }
+
+        Self {
+            config,
+            text,
+            scroll: 0,
+            buttons: Buttons::new(&[("Delete", Key::Char('d')), ("Requeue", Key::Char('i')), ("Close", Key::Esc)]),
+        }
+    }
+}
+
+impl Popup for QueueFile {
+    fn render(&mut self, frame: &mut Frame) {
+        let area = popup::area(frame, 100, 30);
+
+        let text: Vec<Line> = self.text.iter().map(|line| Line::raw(line.clone())).collect();
+        let paragraph = Paragraph::new(text)
+            .scroll((self.scroll, 0))
+            .block(Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(" Queue File (Up/Down: scroll, d: delete, i: requeue, Esc: close) ".to_string())
+            .style(Style::default().fg(Color::Blue)),
+        );
+        frame.render_widget(paragraph, area);
+        self.buttons.render(frame, area);
+    }
+
+    fn input(&mut self, input: Input) -> PopupStatus {
+        match input {
+            Input { key: Key::Esc, .. } => return PopupStatus::Cancel,
+            Input { key: Key::Char('d'), .. } |
+            Input { key: Key::Char('D'), .. } => return PopupStatus::Submit,
+            Input { key: Key::Char('i'), .. } |
+            Input { key: Key::Char('I'), .. } => return PopupStatus::Action(Action::Requeue),
+            Input { key: Key::Down, .. } => self.scroll = self.scroll.saturating_add(1).min(self.text.len() as u16),
+            Input { key: Key::Up, .. } => self.scroll = self.scroll.saturating_sub(1),
+            _input => {}
+        }
+        PopupStatus::Continue
+    }
+
+    fn buttons(&self) -> Option<&Buttons> {
+        Some(&self.buttons)
+    }
+
+    fn submit(&self) -> Option<PopupReqParam> {
+        let queue_file = self.config.queue_file().unwrap();
+        let url = Url::parse(&format!("{}://{}:{}/3.1/queues/{}/{}",
+            self.config.protocol(),
+            self.config.host(),
+            self.config.port(),
+            queue_file.queue(),
+            queue_file.file())).unwrap();
+        let map = HashMap::new();
+
+        Some(PopupReqParam::new(Method::DELETE, url, map))
+    }
+
+    fn undo(&self) -> Option<Undo> {
+        let queue_file = self.config.queue_file().unwrap();
+        Some(Undo::Impossible(format!("deleting {} from the {} queue", queue_file.file(), queue_file.queue())))
+    }
+}
@@ -0,0 +1,240 @@
+use std::{collections::HashMap, fs, io::Write, path::PathBuf, sync::Arc};
+
+use ratatui::{prelude::*, widgets::*};
+use reqwest::{Method, Url};
+use tempfile::TempDir;
+use tui_textarea::{TextArea, Input, Key};
+
+use crate::{config::Config, popup::{self, Popup, PopupReqParam, PopupStatus}, undo::Undo};
+
+const FIELDS: [&str; 3] = [
+    " Queue ",
+    " List ID ",
+    " Message file (.eml) ",
+];
+
+#[derive(Clone)]
+pub struct QueueInject<'a> {
+    config: Config,
+    text_areas: Vec<TextArea<'a>>,
+    focus: usize,
+    text: String,
+    error: Option<String>,
+    // The queue file whose message is injected again, removed once that went through
+    requeue: bool,
+    // Private to the user and removed with the popup, so nobody else can swap the draft
+    drafts: Option<Arc<TempDir>>,
+}
+
+impl<'a> QueueInject<'a> {
+    pub fn new(config: Config) -> Self {
+        let mut text_areas: Vec<TextArea> = FIELDS.iter().map(|title| {
+            let mut text_area = TextArea::default();
+            text_area.set_block(Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .title(title.to_string())
+                .style(Style::default().fg(Color::Blue)),
+            );
+            text_area
+        }).collect();
+        text_areas[0].insert_str("in");
+        if let Some(list) = config.list() {
+            text_areas[1].insert_str(list.list_id());
+        }
+
+        let mut queue_inject = Self {
+            config,
+            text_areas,
+            focus: 2,
+            text: String::new(),
+            error: None,
+            requeue: false,
+            drafts: None,
+        };
+        queue_inject.set_focus(2);
+        queue_inject
+    }
+
+    // Into the queue the message was shunted from, for the list it was posted to
+    pub fn requeue(config: Config) -> Self {
+        let message = config.queue_file().unwrap().message();
+        let mut queue_inject = Self::new(config);
+        match message {
+            Ok(message) => {
+                if let Some(whichq) = message.metadata_value("whichq") {
+                    queue_inject.text_areas[0] = queue_inject.field(0, &whichq);
+                }
+                if let Some(listid) = message.metadata_value("listid") {
+                    queue_inject.text_areas[1] = queue_inject.field(1, &listid);
+                }
+                queue_inject.text = message.text();
+            }
+            Err(e) => queue_inject.error = Some(e),
+        }
+        queue_inject.requeue = true;
+        queue_inject.set_focus(0);
+        queue_inject
+    }
+
+    fn field(&self, i: usize, value: &str) -> TextArea<'a> {
+        let mut text_area = TextArea::new(vec![value.to_string()]);
+        text_area.set_block(Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(FIELDS[i].to_string())
+            .style(Style::default().fg(Color::Blue)),
+        );
+        text_area
+    }
+
+    // Requeueing has no file to pick, the message comes from the queue file
+    fn fields(&self) -> usize {
+        if self.requeue { FIELDS.len() - 1 } else { FIELDS.len() }
+    }
+
+    fn url(&self, path: &str) -> Url {
+        Url::parse(&format!("{}://{}:{}/3.1/queues/{}",
+            self.config.protocol(),
+            self.config.host(),
+            self.config.port(),
+            path)).unwrap()
+    }
+
+    fn set_focus(&mut self, focus: usize) {
+        self.focus = focus;
+        for (i, text_area) in self.text_areas.iter_mut().enumerate() {
+            if i == focus {
+                text_area.set_cursor_style(Style::default().add_modifier(Modifier::REVERSED));
+            } else {
+                text_area.set_cursor_style(Style::default());
+            }
+        }
+    }
+
+    // The file composed in $EDITOR, started with the headers Mailman needs
+    fn draft(&mut self) -> std::io::Result<PathBuf> {
+        let drafts = match &self.drafts {
+            Some(drafts) => drafts.clone(),
+            None => {
+                let mut builder = tempfile::Builder::new();
+                builder.prefix("marge-");
+                #[cfg(unix)]
+                builder.permissions(std::os::unix::fs::PermissionsExt::from_mode(0o700));
+                let drafts = Arc::new(builder.tempdir()?);
+                self.drafts = Some(drafts.clone());
+                drafts
+            }
+        };
+
+        // Composing again continues the draft
+        let path = drafts.path().join("draft.eml");
+        if !path.exists() {
+            let to = self.config.list().map_or(String::new(), |list| list.fqdn_listname());
+            let mut options = fs::OpenOptions::new();
+            options.write(true).create_new(true);
+            #[cfg(unix)]
+            std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+            let mut file = options.open(&path)?;
+            write!(file, "From: \nTo: {}\nSubject: \n\n", to)?;
+        }
+        Ok(path)
+    }
+
+    fn value(&self, i: usize) -> String {
+        self.text_areas[i].lines()[0].trim().to_string()
+    }
+}
+
+impl Popup for QueueInject<'_> {
+    fn render(&mut self, frame: &mut Frame) {
+        let fields = self.fields();
+        let area = popup::area(frame, 80, 3 * fields as u16 + 1);
+        let mut constraints = vec![Constraint::Length(3); fields];
+        constraints.push(Constraint::Length(1));
+        let chunks = Layout::vertical(constraints).split(area);
+        for (text_area, chunk) in self.text_areas.iter().take(fields).zip(chunks.iter()) {
+            frame.render_widget(text_area, *chunk);
+        }
+
+        let hint = match &self.error {
+            Some(error) => Line::styled(error.clone(), Style::default().fg(Color::Red)),
+            None if self.requeue => Line::raw("Tab/Up/Down: field, Enter: requeue and delete the queue file, Esc: cancel"),
+            None => Line::raw("Tab/Up/Down: field, Ctrl-E: compose in $EDITOR, Enter: inject, Esc: cancel"),
+        };
+        frame.render_widget(Paragraph::new(hint).style(Style::default().fg(Color::Blue)), chunks[fields]);
+    }
+
+    fn input(&mut self, input: Input) -> PopupStatus {
+        let mut status = PopupStatus::Continue;
+        match input {
+            Input { key: Key::Esc, .. } => status = PopupStatus::Cancel,
+            Input { key: Key::Enter, .. } => {
+                if self.value(0).is_empty() || self.value(1).is_empty() {
+                    self.error = Some("Queue and list ID must not be empty".to_string());
+                } else if self.requeue {
+                    if !self.text.is_empty() {
+                        status = PopupStatus::Submit;
+                    }
+                } else {
+                    match fs::read_to_string(self.value(2)) {
+                        Ok(text) if !text.trim().is_empty() => {
+                            self.text = text;
+                            status = PopupStatus::Submit;
+                        }
+                        Ok(_) => self.error = Some("The message file is empty".to_string()),
+                        Err(e) => self.error = Some(format!("Can't read message file: {}", e)),
+                    }
+                }
+            }
+            Input { key: Key::Char('e'), ctrl: true, .. } if !self.requeue => {
+                match self.draft() {
+                    Ok(path) => {
+                        self.text_areas[2] = self.field(2, &path.display().to_string());
+                        self.set_focus(2);
+                        self.error = None;
+                        status = PopupStatus::Edit(path);
+                    }
+                    Err(e) => self.error = Some(format!("Can't create draft: {}", e)),
+                }
+            }
+            Input { key: Key::Tab, .. } |
+            Input { key: Key::Down, .. } => self.set_focus((self.focus + 1) % self.fields()),
+            Input { key: Key::Up, .. } => self.set_focus((self.focus + self.fields() - 1) % self.fields()),
+            input => { self.text_areas[self.focus].input(input); }
+        }
+
+        status
+    }
+
+    fn paste(&mut self, text: &str) {
+        popup::paste_line(&mut self.text_areas[self.focus], text);
+    }
+
+    fn submit(&self) -> Option<PopupReqParam> {
+        let mut map = HashMap::new();
+        map.insert("list_id".to_string(), self.value(1));
+        map.insert("text".to_string(), self.text.clone());
+
+        Some(PopupReqParam::new(Method::POST, self.url(&self.value(0)), map))
+    }
+
+    fn submit_all(&self) -> Vec<PopupReqParam> {
+        let mut params: Vec<PopupReqParam> = self.submit().into_iter().collect();
+        // Like mailman unshunt, the message leaves the queue it waited in, but only once it's back
+        if self.requeue {
+            let queue_file = self.config.queue_file().unwrap();
+            let url = self.url(&format!("{}/{}", queue_file.queue(), queue_file.file()));
+            params.push(PopupReqParam::new(Method::DELETE, url, HashMap::new()).if_previous_succeeded());
+        }
+        params
+    }
+
+    fn undo(&self) -> Option<Undo> {
+        if self.requeue {
+            let queue_file = self.config.queue_file().unwrap();
+            return Some(Undo::Impossible(format!("requeueing {} from the {} queue", queue_file.file(), queue_file.queue())));
+        }
+        None
+    }
+}
@@ -1,20 +1,29 @@
 use std::collections::HashMap;
 
+use color_eyre::eyre::{eyre, Result};
 use reqwest::{Method, Client, Url};
+use serde::de::DeserializeOwned;
+use serde_json::{Map, value::Value};
 
-use crate::{config::Config, popup::PopupReqParam};
+use crate::{audit, config::Config, popup::PopupReqParam, response::{Response, ResponseType}};
 
 pub enum ReqType {
     Domains,
     Lists,
     Members,
     Messages,
+    Templates(String),
+    HeaderMatches,
+    Archivers,
+    System(String),
+    Queue(String),
+    Preferences(String),
     Popup(PopupReqParam),
 }
 
 pub async fn request(client: &mut Client, req_t: ReqType, config: &Config) -> Result<reqwest::Response, reqwest::Error> {
     let mut method = Method::GET;
-    let mut map: HashMap<String, String>  = HashMap::new();
+    let mut map: Map<String, Value> = Map::new();
     let url = match req_t {
         ReqType::Domains => {
             Url::parse(&format!("{}://{}:{}/3.1/domains",
@@ -49,15 +58,107 @@ pub async fn request(client: &mut Client, req_t: ReqType, config: &Config) -> Re
                 config.port(),
                 config.list().unwrap().fqdn_listname())).unwrap()
         }
+        ReqType::Templates(scope) => {
+            Url::parse(&format!("{}://{}:{}/3.1/{}/uris",
+                config.protocol(),
+                config.host(),
+                config.port(),
+                scope)).unwrap()
+        }
+        ReqType::HeaderMatches => {
+            Url::parse(&format!("{}://{}:{}/3.1/lists/{}/header-matches",
+                config.protocol(),
+                config.host(),
+                config.port(),
+                config.list().unwrap().list_id())).unwrap()
+        }
+        ReqType::Archivers => {
+            Url::parse(&format!("{}://{}:{}/3.1/lists/{}/archivers",
+                config.protocol(),
+                config.host(),
+                config.port(),
+                config.list().unwrap().list_id())).unwrap()
+        }
+        ReqType::System(part) => {
+            Url::parse(&format!("{}://{}:{}/3.1/{}",
+                config.protocol(),
+                config.host(),
+                config.port(),
+                part)).unwrap()
+        }
+        ReqType::Queue(name) => {
+            Url::parse(&format!("{}://{}:{}/3.1/queues/{}",
+                config.protocol(),
+                config.host(),
+                config.port(),
+                name)).unwrap()
+        }
+        ReqType::Preferences(member_id) => {
+            Url::parse(&format!("{}://{}:{}/3.1/members/{}/preferences",
+                config.protocol(),
+                config.host(),
+                config.port(),
+                member_id)).unwrap()
+        }
         ReqType::Popup(param) => {
             method = param.method();
             map = param.map();
             param.url()
         }
     };
-    client.request(method, url)
+    let result = client.request(method.clone(), url.clone())
         .basic_auth(config.username(), Some(config.password()))
         .json(&map)
         .send()
-        .await
-}
\ No newline at end of file
+        .await;
+    let status = match &result {
+        Ok(response) => response.status().to_string(),
+        Err(e) => format!("error: {}", e),
+    };
+    let _ = audit::record(config, &method, &url, &map, status);
+    result
+}
+
+// For the command line, where there's no view to show a response in: GETs /3.1/<path> and parses it
+pub async fn get_json<T: DeserializeOwned>(client: &mut Client, config: &Config, path: &str) -> Result<T> {
+    let url = Url::parse(&format!("{}://{}:{}/3.1/{}",
+        config.protocol(),
+        config.host(),
+        config.port(),
+        path))?;
+    let param = PopupReqParam::new(Method::GET, url, HashMap::new());
+    let resp = request(client, ReqType::Popup(param), config).await;
+    let response = Response::new(resp, ResponseType::Popup).await;
+    if !response.is_success() {
+        return Err(eyre!("Can't load {}: {}", path, response.status()));
+    }
+    Ok(serde_json::from_str(&response.text())?)
+}
+
+// The command line's counterpart of Marge::send_batch: keeps going after failures and reports them on stderr
+pub async fn send_all(client: &mut Client, config: &Config, params: Vec<PopupReqParam>) -> Result<()> {
+    let total = params.len();
+    let mut failures = 0;
+    let mut previous_succeeded = true;
+    for param in params {
+        let label = param.label();
+        if param.needs_previous() && !previous_succeeded {
+            eprintln!("Skipped {}", label);
+            failures += 1;
+            continue;
+        }
+        let resp = request(client, ReqType::Popup(param), config).await;
+        let response = Response::new(resp, ResponseType::Popup).await;
+        previous_succeeded = response.is_success();
+        if !previous_succeeded {
+            eprintln!("Failed {}: {}", label, response.status());
+            failures += 1;
+        }
+    }
+    if failures > 0 {
+        Err(eyre!("{} of {} changes failed or were skipped", failures, total))
+    } else {
+        println!("All {} changes applied", total);
+        Ok(())
+    }
+}
@@ -1,37 +1,73 @@
+use serde_json::value::Value;
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum ResponseType {
     Domains,
     Lists,
     Members,
     Messages,
+    Templates(String),
+    HeaderMatches,
+    Archivers,
+    System(String),
+    Queue(String),
+    Bounces,
+    // The preferences of the member with the given id, loaded for the bounce view
+    Preferences(String),
+    // The local audit log, not a response from the server
+    Audit,
     Popup,
+    PopupData(String),
 }
 
 #[derive(Clone)]
 pub struct Response {
     response_type: ResponseType,
+    status_code: Option<u16>,
     status: String,
     text: String,
 }
 
 impl Response {
     pub async fn new(result: Result<reqwest::Response, reqwest::Error>, response_type: ResponseType) -> Self {
+        let mut status_code = None;
         let status: String;
-        let mut text = "Error (see status bar below for details)".to_string();
-    
+        let text: String;
+
         match result {
             Ok(body) => {
                 let bstatus = body.status();
-                status = format!("{}: {}", bstatus.as_str(), bstatus.canonical_reason().unwrap());
-                if bstatus == 200 {
-                    text = body.text().await.unwrap();
+                status_code = Some(bstatus.as_u16());
+                let reason = bstatus.canonical_reason().unwrap_or("Unknown status");
+                // Mailman tells where a created resource lives, e.g. the queue file of an injected message
+                let location = body.headers()
+                    .get(reqwest::header::LOCATION)
+                    .and_then(|location| location.to_str().ok())
+                    .map(str::to_string);
+                let btext = body.text().await.unwrap_or_default();
+                if bstatus.is_success() {
+                    status = match location {
+                        Some(location) => format!("{}: {} ({})", bstatus.as_str(), reason, location),
+                        None => format!("{}: {}", bstatus.as_str(), reason),
+                    };
+                    text = btext;
+                } else if let Some(description) = error_description(&btext) {
+                    status = format!("{}: {} ({})", bstatus.as_str(), reason, description);
+                    text = description;
+                } else {
+                    status = format!("{}: {}", bstatus.as_str(), reason);
+                    text = reason.to_string();
                 }
             }
-            Err(e) => status = e.to_string(),
-        }; 
+            Err(e) => {
+                status = e.to_string();
+                text = status.clone();
+            }
+        };
 
         Self {
             response_type,
+            status_code,
             status,
             text,
         }
@@ -48,4 +84,32 @@ impl Response {
     pub fn status(&self) -> String {
         self.status.clone()
     }
-}
\ No newline at end of file
+
+    pub fn status_code(&self) -> Option<u16> {
+        self.status_code
+    }
+
+    // 200, 201, 202 and 204 are all fine for us; Mailman answers POST with 201 and DELETE with 204
+    pub fn is_success(&self) -> bool {
+        matches!(self.status_code, Some(code) if (200..300).contains(&code))
+    }
+}
+
+// Mailman reports errors as JSON like {"title": "400 Bad Request", "description": "Member already subscribed"}
+fn error_description(text: &str) -> Option<String> {
+    match serde_json::from_str::<Value>(text) {
+        Ok(value) => match value.get("description") {
+            Some(Value::String(description)) => Some(description.clone()),
+            Some(Value::Null) | None => value.get("title").and_then(Value::as_str).map(str::to_string),
+            Some(description) => Some(description.to_string()),
+        },
+        Err(_) => {
+            let text = text.trim();
+            if text.is_empty() {
+                None
+            } else {
+                Some(text.to_string())
+            }
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use std::{collections::HashMap, path::Path};
+
+use color_eyre::eyre::{eyre, Result};
+use reqwest::{Client, Method, Url};
+
+use crate::{addresses, config::Config, lists, members::{self, Members}, popup::PopupReqParam, request::{self, get_json}};
+
+// What it takes to make a list's members match an authoritative source
+#[derive(Clone)]
+pub struct Plan {
+    additions: Vec<(String, String)>,
+    removals: Vec<members::Entry>,
+}
+
+impl Plan {
+    pub fn new(source: &str, roster: &[members::Entry], never_remove: bool) -> Self {
+        let wanted = addresses::parse_named(source);
+        let additions = wanted.iter()
+            .filter(|(address, _)| !roster.iter().any(|member| member.email().to_lowercase() == *address))
+            .cloned()
+            .collect();
+        let removals = if never_remove {
+            Vec::new()
+        } else {
+            roster.iter()
+                .filter(|member| !wanted.iter().any(|(address, _)| *address == member.email().to_lowercase()))
+                .cloned()
+                .collect()
+        };
+        Self {
+            additions,
+            removals,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.additions.is_empty() && self.removals.is_empty()
+    }
+
+    pub fn summary(&self) -> String {
+        format!("{} to add, {} to remove", self.additions.len(), self.removals.len())
+    }
+
+    pub fn removals(&self) -> Vec<members::Entry> {
+        self.removals.clone()
+    }
+
+    pub fn lines(&self) -> Vec<String> {
+        let additions = self.additions.iter().map(|(address, display_name)| {
+            if display_name.is_empty() {
+                format!("+ {}", address)
+            } else {
+                format!("+ {} ({})", address, display_name)
+            }
+        });
+        let removals = self.removals.iter().map(|member| format!("- {}", member.email()));
+        additions.chain(removals).collect()
+    }
+
+    pub fn requests(&self, config: &Config, list_id: &str) -> Vec<PopupReqParam> {
+        let mut params = Vec::new();
+        for (address, display_name) in &self.additions {
+            let url = Url::parse(&format!("{}://{}:{}/3.1/members",
+                config.protocol(),
+                config.host(),
+                config.port())).unwrap();
+            let mut map = HashMap::new();
+            map.insert("list_id".to_string(), list_id.to_string());
+            map.insert("subscriber".to_string(), address.clone());
+            map.insert("display_name".to_string(), display_name.clone());
+            map.insert("pre_verified".to_string(), "true".to_string());
+            map.insert("pre_confirmed".to_string(), "true".to_string());
+            map.insert("pre_approved".to_string(), "true".to_string());
+            map.insert("send_welcome_message".to_string(), "false".to_string());
+            params.push(PopupReqParam::new(Method::POST, url, map).with_label(format!("adding {}", address)));
+        }
+        for member in &self.removals {
+            let url = Url::parse(&format!("{}://{}:{}/3.1/members/{}",
+                config.protocol(),
+                config.host(),
+                config.port(),
+                member.member_id())).unwrap();
+            let mut map = HashMap::new();
+            map.insert("pre_approved".to_string(), "true".to_string());
+            map.insert("pre_confirmed".to_string(), "true".to_string());
+            params.push(PopupReqParam::new(Method::DELETE, url, map).with_label(format!("removing {}", member.email())));
+        }
+        params
+    }
+}
+
+// A source without a single address is almost certainly a broken export, not an empty list
+pub fn check_source(source: &str, never_remove: bool) -> Result<(), String> {
+    if !never_remove && addresses::parse(source).is_empty() {
+        Err("The source holds no addresses; refusing to remove every member".to_string())
+    } else {
+        Ok(())
+    }
+}
+
+// `marge sync`, meant to be run from cron: prints the differences and, unless it's a dry run, applies them
+pub async fn run(client: &mut Client, config: &Config, list: &str, file: &Path, never_remove: bool, dry_run: bool) -> Result<()> {
+    let source = std::fs::read_to_string(file)
+        .map_err(|e| eyre!("Can't read {}: {}", file.display(), e))?;
+    check_source(&source, never_remove).map_err(|e| eyre!(e))?;
+    let list: lists::Entry = get_json(client, config, &format!("lists/{}", list)).await?;
+    let members: Members = get_json(client, config, &format!("lists/{}/roster/member", list.list_id())).await?;
+    let plan = Plan::new(&source, &members.entries().unwrap_or_default(), never_remove);
+    for line in plan.lines() {
+        println!("{}", line);
+    }
+    println!("{}: {}", list.fqdn_listname(), plan.summary());
+    if dry_run || plan.is_empty() {
+        return Ok(());
+    }
+
+    request::send_all(client, config, plan.requests(config, &list.list_id())).await
+}
@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+
+use serde::{Serialize, Deserialize};
+use serde_json::value::Value;
+
+// The parts of the dashboard, each fetched from /3.1/<part>
+pub const PARTS: [&str; 3] = ["system/versions", "queues", "system/pipelines"];
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Versions {
+    api_version: String,
+    http_etag: String,
+    mailman_version: String,
+    python_version: String,
+    self_link: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Queues {
+    entries: Option<Vec<Entry>>,
+    http_etag: String,
+    start: u32,
+    total_size: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Entry {
+    count: u32,
+    directory: String,
+    files: Vec<String>,
+    http_etag: String,
+    name: String,
+    self_link: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct System {
+    versions: Option<Versions>,
+    queues: Option<Queues>,
+    pipelines: Vec<String>,
+    previous_counts: HashMap<String, u32>,
+}
+
+impl System {
+    pub fn update(&mut self, part: &str, text: &str) -> Result<(), serde_json::Error> {
+        match part {
+            "system/versions" => self.versions = Some(serde_json::from_str(text)?),
+            "queues" => {
+                if let Some(entries) = self.queues.as_ref().and_then(|queues| queues.entries.as_ref()) {
+                    self.previous_counts = entries.iter().map(|entry| (entry.name.clone(), entry.count)).collect();
+                }
+                self.queues = Some(serde_json::from_str(text)?);
+            }
+            _ => {
+                let value: Value = serde_json::from_str(text)?;
+                self.pipelines = value.get("pipelines")
+                    .and_then(Value::as_array)
+                    .map_or(Vec::new(), |pipelines| pipelines.iter().filter_map(|p| p.as_str().map(str::to_string)).collect());
+            }
+        }
+        Ok(())
+    }
+
+    pub fn table(&self) -> (Vec<String>, Vec<Vec<String>>) {
+        let header = vec!["component".to_string(), "status".to_string()];
+        let mut rows = Vec::new();
+        if let Some(versions) = &self.versions {
+            rows.push(vec!["Mailman".to_string(), versions.mailman_version.clone()]);
+            rows.push(vec!["REST API".to_string(), versions.api_version.clone()]);
+            rows.push(vec!["Python".to_string(), versions.python_version.clone()]);
+        }
+        if !self.pipelines.is_empty() {
+            rows.push(vec!["Pipelines".to_string(), self.pipelines.join(", ")]);
+        }
+        if let Some(entries) = self.entries() {
+            for entry in entries {
+                // A queue growing between two refreshes hints at a stuck runner
+                let trend = match self.previous_counts.get(&entry.name) {
+                    Some(previous) if entry.count > *previous => format!(" (+{})", entry.count - previous),
+                    Some(previous) if entry.count < *previous => format!(" (-{})", previous - entry.count),
+                    _ => String::new(),
+                };
+                rows.push(vec![format!("Queue {}", entry.name), format!("{} files{}", entry.count, trend)]);
+            }
+        }
+        (header, rows)
+    }
+
+    // The queue shown in the given row of table()
+    pub fn queue(&self, row: usize) -> Option<String> {
+        let mut offset = 0;
+        if self.versions.is_some() {
+            offset += 3;
+        }
+        if !self.pipelines.is_empty() {
+            offset += 1;
+        }
+        let entries = self.entries()?;
+        entries.get(row.checked_sub(offset)?).map(|entry| entry.name.clone())
+    }
+
+    pub fn entries(&self) -> Option<Vec<Entry>> {
+        self.queues.as_ref().and_then(|queues| queues.entries.clone())
+    }
+}
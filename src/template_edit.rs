@@ -0,0 +1,82 @@
+use std::collections::HashMap;
+
+use ratatui::{prelude::*, widgets::*};
+use reqwest::{Method, Url};
+use tui_textarea::{TextArea, Input, Key};
+
+use crate::{config::Config, popup::{self, Popup, PopupReqParam, PopupStatus}};
+
+#[derive(Clone)]
+pub struct TemplateEdit<'a> {
+    config: Config,
+    text_area: TextArea<'a>,
+}
+
+impl<'a> TemplateEdit<'a> {
+    pub fn new(config: Config) -> Self {
+        let template = config.template().unwrap();
+        let mut text_area = TextArea::default();
+        text_area.set_block(Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(format!(" URI for {} (empty resets to default) ", template.name()))
+            .style(Style::default().fg(Color::Blue)),
+        );
+        if let Some(uri) = template.uri() {
+            text_area.insert_str(uri);
+        }
+
+        Self {
+            config,
+            text_area,
+        }
+    }
+}
+
+impl Popup for TemplateEdit<'_> {
+    fn render(&mut self, frame: &mut Frame) {
+        let area = popup::area(frame, 80, 3);
+
+        frame.render_widget(&self.text_area, area);
+    }
+
+    fn input(&mut self, input: Input) -> PopupStatus {
+        let mut status = PopupStatus::Continue;
+        match input {
+            Input { key: Key::Esc, .. } => status = PopupStatus::Cancel,
+            Input { key: Key::Enter, .. } => status = PopupStatus::Submit,
+            input => { self.text_area.input(input); }
+        }
+
+        status
+    }
+
+    fn paste(&mut self, text: &str) {
+        popup::paste_line(&mut self.text_area, text);
+    }
+
+    fn submit(&self) -> Option<PopupReqParam> {
+        let template = self.config.template().unwrap();
+        let uri = self.text_area.lines()[0].trim().to_string();
+        if uri.is_empty() {
+            let url = Url::parse(&format!("{}://{}:{}/3.1/{}/uris/{}",
+                self.config.protocol(),
+                self.config.host(),
+                self.config.port(),
+                template.scope(),
+                template.name())).unwrap();
+
+            return Some(PopupReqParam::new(Method::DELETE, url, HashMap::new()));
+        }
+
+        let url = Url::parse(&format!("{}://{}:{}/3.1/{}/uris",
+            self.config.protocol(),
+            self.config.host(),
+            self.config.port(),
+            template.scope())).unwrap();
+        let mut map = HashMap::new();
+        map.insert(template.name(), uri);
+
+        Some(PopupReqParam::new(Method::PATCH, url, map))
+    }
+}
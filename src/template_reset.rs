@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+
+use ratatui::{prelude::*, widgets::*};
+use reqwest::{Method, Url};
+use tui_textarea::{Input, Key};
+
+use crate::{config::Config, popup::{self, Buttons, Popup, PopupReqParam, PopupStatus}, undo::Undo};
+
+#[derive(Clone)]
+pub struct TemplateReset<'a> {
+    config: Config,
+    paragraph: Paragraph<'a>,
+    buttons: Buttons,
+}
+
+impl<'a> TemplateReset<'a> {
+    pub fn new(config: Config) -> Self {
+        let line = Line::raw("Are you sure? Type 'y' or Enter for yes or 'n' or Esc for no");
+        let paragraph = Paragraph::new(line)
+            .block(Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(" Reset Template to Default? ".to_string())
+            .style(Style::default().fg(Color::Blue)),
+        );
+
+        Self {
+            config,
+            paragraph,
+            buttons: Buttons::new(&[("Yes", Key::Enter), ("No", Key::Esc)]),
+        }
+    }
+}
+
+impl Popup for TemplateReset<'_> {
+    fn render(&mut self, frame: &mut Frame) {
+        let area = popup::area(frame, 62, 4);
+
+        frame.render_widget(self.paragraph.clone(), area);
+        self.buttons.render(frame, area);
+    }
+
+    fn input(&mut self, input: Input) -> PopupStatus {
+        let mut status = PopupStatus::Continue;
+        match input {
+            Input { key: Key::Esc, .. } |
+            Input { key: Key::Char('n'), .. } |
+            Input { key: Key::Char('N'), .. } => status = PopupStatus::Cancel,
+            Input { key: Key::Enter, .. } |
+            Input { key: Key::Char('y'), .. } |
+            Input { key: Key::Char('Y'), .. } => status = PopupStatus::Submit,
+            _input => {}
+        }
+
+        status
+    }
+
+    fn buttons(&self) -> Option<&Buttons> {
+        Some(&self.buttons)
+    }
+
+    fn submit(&self) -> Option<PopupReqParam> {
+        let template = self.config.template().unwrap();
+        let url = Url::parse(&format!("{}://{}:{}/3.1/{}/uris/{}",
+            self.config.protocol(),
+            self.config.host(),
+            self.config.port(),
+            template.scope(),
+            template.name())).unwrap();
+        let map = HashMap::new();
+
+        Some(PopupReqParam::new(Method::DELETE, url, map))
+    }
+
+    fn undo(&self) -> Option<Undo> {
+        let template = self.config.template().unwrap();
+        // Without an own URI the template already was the default
+        let uri = template.uri()?;
+        let description = format!("resetting {}", template.name());
+        let url = Url::parse(&format!("{}://{}:{}/3.1/{}/uris",
+            self.config.protocol(),
+            self.config.host(),
+            self.config.port(),
+            template.scope())).unwrap();
+        let mut map = HashMap::new();
+        map.insert(template.name(), uri);
+        // Mailman applies them to the URIs of the same PATCH
+        if let Some(username) = template.username() {
+            map.insert("username".to_string(), username);
+        }
+        if let Some(password) = template.password() {
+            map.insert("password".to_string(), password);
+        }
+
+        Some(Undo::Restore(description, vec![PopupReqParam::new(Method::PATCH, url, map)]))
+    }
+}
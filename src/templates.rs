@@ -0,0 +1,140 @@
+use std::collections::BTreeMap;
+
+use serde::{Serialize, Deserialize};
+
+// Templates Mailman lets lists and domains override, see
+// https://docs.mailman3.org/projects/mailman/en/latest/src/mailman/rest/docs/templates.html
+pub const TEMPLATE_NAMES: [&str; 30] = [
+    "list:admin:action:post",
+    "list:admin:action:subscribe",
+    "list:admin:action:unsubscribe",
+    "list:admin:notice:disable",
+    "list:admin:notice:increment",
+    "list:admin:notice:pending",
+    "list:admin:notice:removal",
+    "list:admin:notice:subscribe",
+    "list:admin:notice:unrecognized",
+    "list:admin:notice:unsubscribe",
+    "list:member:digest:footer",
+    "list:member:digest:header",
+    "list:member:digest:masthead",
+    "list:member:generic:footer",
+    "list:member:regular:footer",
+    "list:member:regular:header",
+    "list:user:action:invite",
+    "list:user:action:subscribe",
+    "list:user:action:unsubscribe",
+    "list:user:notice:goodbye",
+    "list:user:notice:hold",
+    "list:user:notice:no-more-today",
+    "list:user:notice:post",
+    "list:user:notice:probe",
+    "list:user:notice:refuse",
+    "list:user:notice:rejected",
+    "list:user:notice:warning",
+    "list:user:notice:welcome",
+    "domain:admin:notice:new-list",
+    "help",
+];
+
+#[derive(Debug, Clone)]
+pub struct Templates {
+    entries: Vec<Entry>,
+}
+
+// GET .../uris, a collection of the templates the list or domain overrides
+#[derive(Deserialize, Debug, Clone)]
+pub struct Uris {
+    #[serde(default)]
+    entries: Vec<Uri>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct Uri {
+    name: String,
+    uri: String,
+    // For URIs behind HTTP authentication
+    #[serde(default)]
+    username: Option<String>,
+    #[serde(default)]
+    password: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Entry {
+    scope: String,
+    name: String,
+    uri: Option<String>,
+    #[serde(default)]
+    username: Option<String>,
+    #[serde(default)]
+    password: Option<String>,
+}
+
+impl Templates {
+    // scope is the resource the templates belong to, i.e. "lists/<list_id>" or "domains/<mail_host>"
+    pub fn new(scope: &str, text: &str) -> Result<Self, serde_json::Error> {
+        let uris = serde_json::from_str::<Uris>(text)?.entries;
+        let entry = |name: &str| {
+            let uri = uris.iter().find(|uri| uri.name == name);
+            Entry {
+                scope: scope.to_string(),
+                name: name.to_string(),
+                uri: uri.map(|uri| uri.uri.clone()),
+                username: uri.and_then(|uri| uri.username.clone()),
+                password: uri.and_then(|uri| uri.password.clone()),
+            }
+        };
+        let mut entries: Vec<Entry> = TEMPLATE_NAMES.iter().map(|name| entry(name)).collect();
+        for uri in &uris {
+            if !TEMPLATE_NAMES.contains(&uri.name.as_str()) {
+                entries.push(entry(&uri.name));
+            }
+        }
+
+        Ok(Self {
+            entries,
+        })
+    }
+
+    pub fn table(&self) -> (Vec<String>, Vec<Vec<String>>) {
+        let header = vec!["template".to_string(), "uri".to_string()];
+        let rows = self.entries.iter().map(|entry| {
+            vec![entry.name(), entry.uri().unwrap_or("(default)".to_string())]
+        }).collect();
+        (header, rows)
+    }
+
+    pub fn entries(&self) -> Option<Vec<Entry>> {
+        Some(self.entries.clone())
+    }
+}
+
+impl Uris {
+    // Template name to URI
+    pub fn uris(&self) -> BTreeMap<String, String> {
+        self.entries.iter().map(|entry| (entry.name.clone(), entry.uri.clone())).collect()
+    }
+}
+
+impl Entry {
+    pub fn scope(&self) -> String {
+        self.scope.clone()
+    }
+
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    pub fn uri(&self) -> Option<String> {
+        self.uri.clone()
+    }
+
+    pub fn username(&self) -> Option<String> {
+        self.username.clone()
+    }
+
+    pub fn password(&self) -> Option<String> {
+        self.password.clone()
+    }
+}
@@ -4,7 +4,7 @@ use color_eyre::eyre::Result;
 
 use crossterm::{
     cursor, 
-    event::{Event as CrosstermEvent, EventStream, KeyEvent, KeyEventKind, MouseEvent},
+    event::{DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture, Event as CrosstermEvent, EventStream, KeyEvent, KeyEventKind, MouseEvent},
     terminal::{EnterAlternateScreen, LeaveAlternateScreen}};
 use ratatui::{backend::CrosstermBackend as Backend, Terminal};
 use tokio::{
@@ -14,6 +14,7 @@ use tokio::{
 use tokio_util::sync::CancellationToken;
 use futures::{FutureExt, StreamExt};
 
+#[allow(dead_code)]
 pub enum Event {
     Init,
     Error,
@@ -47,6 +48,16 @@ impl Tui {
         let task = tokio::spawn(async {});
         let (event_tx, event_rx) = mpsc::unbounded_channel();
 
+        // Once here rather than in enter(), which runs again after every trip to $EDITOR;
+        // the command line subcommands never touch the terminal, so there's nothing to reset
+        let panic_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |panic| {
+            if crossterm::terminal::is_raw_mode_enabled().unwrap_or(false) {
+                Self::reset().expect("failed to reset the terminal");
+            }
+            panic_hook(panic);
+        }));
+
         Ok(Self {
             terminal,
             frame_rate,
@@ -143,15 +154,11 @@ impl Tui {
         crossterm::execute!(
             std::io::stderr(),
             EnterAlternateScreen,
+            EnableMouseCapture,
+            EnableBracketedPaste,
             cursor::Hide
         )?;
 
-        let panic_hook = panic::take_hook();
-        panic::set_hook(Box::new(move |panic| {
-            Self::reset().expect("failed to reset the terminal");
-            panic_hook(panic);
-        }));
-
         self.start();
         Ok(())
     }
@@ -162,6 +169,8 @@ impl Tui {
             self.flush()?;
             crossterm::execute!(
                 std::io::stderr(),
+                DisableBracketedPaste,
+                DisableMouseCapture,
                 LeaveAlternateScreen,
                 cursor::Show
             )?;
@@ -190,6 +199,8 @@ impl Tui {
         crossterm::terminal::disable_raw_mode()?;
         crossterm::execute!(
             io::stderr(),
+            DisableBracketedPaste,
+            DisableMouseCapture,
             LeaveAlternateScreen,
             cursor::Show
         )?;
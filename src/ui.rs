@@ -1,12 +1,23 @@
+use std::cmp::Ordering;
+use std::collections::BTreeSet;
+
 use ratatui::{prelude::*, widgets::*};
+use crossterm::event::{KeyCode, KeyEvent};
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, PartialEq)]
 pub enum MenuItem {
     Domains,
     Lists,
     Members,
     Messages,
+    System,
     Configure,
+    Templates,
+    HeaderMatches,
+    Archivers,
+    Queue,
+    Bounces,
+    Audit,
 }
 
 impl From<MenuItem> for Option<usize> {
@@ -16,7 +27,15 @@ impl From<MenuItem> for Option<usize> {
             MenuItem::Lists => Some(1),
             MenuItem::Members => Some(2),
             MenuItem::Messages => Some(3),
-            MenuItem::Configure => Some(4)
+            MenuItem::System => Some(4),
+            MenuItem::Configure => Some(5),
+            // Views below a list or domain don't have a tab of their own
+            MenuItem::Templates |
+            MenuItem::HeaderMatches |
+            MenuItem::Archivers |
+            MenuItem::Queue |
+            MenuItem::Bounces |
+            MenuItem::Audit => None,
         }        
     }
 }
@@ -26,9 +45,18 @@ pub struct Ui {
     active_menu_item: MenuItem,
     sel_domain: Option<String>,
     sel_list: Option<String>,
-    list_vec: Vec<String>,
-    state: ListState,
+    header: Option<Vec<String>>,
+    rows: Vec<Vec<String>>,
+    view: Vec<usize>,
+    filter: String,
+    searching: bool,
+    sort: Option<(usize, bool)>,
+    marked: BTreeSet<usize>,
+    state: TableState,
     status: String,
+    // Where the last render put the tabs and the table, for mouse clicks
+    tab_areas: Vec<Rect>,
+    table_area: Rect,
 }
 
 impl Ui {
@@ -38,13 +66,20 @@ impl Ui {
             "Lists".to_string(),
             "Members".to_string(),
             "Messages".to_string(),
+            "System".to_string(),
             "Configure".to_string(),
             "Quit".to_string()];
         let active_menu_item = MenuItem::Domains;
         let sel_domain = None;
         let sel_list = None;
-        let list_vec = vec!["waiting".to_string()];
-        let mut state = ListState::default();
+        let header = None;
+        let rows = vec![vec!["waiting".to_string()]];
+        let view = vec![0];
+        let filter = String::new();
+        let searching = false;
+        let sort = None;
+        let marked = BTreeSet::new();
+        let mut state = TableState::default();
         state.select(Some(0));
         let status = String::new();
 
@@ -53,9 +88,17 @@ impl Ui {
             active_menu_item,
             sel_domain,
             sel_list,
-            list_vec,
+            header,
+            rows,
+            view,
+            filter,
+            searching,
+            sort,
+            marked,
             state,
             status,
+            tab_areas: Vec::new(),
+            table_area: Rect::default(),
         }
     }
 
@@ -78,27 +121,36 @@ impl Ui {
         let menu: Vec<Line> = self.menu_titles
             .iter()
             .map(|t| {
-                if *t == "Messages".to_string() {
-                    Line::from(vec![
-                        Span::styled("Me", Style::default().fg(Color::LightRed)),
-                        Span::styled("s", Style::default().fg(Color::Red).add_modifier(Modifier::UNDERLINED)),
-                        Span::styled("sages", Style::default().fg(Color::LightRed))
-                    ])
-                } else {
-                    let (first, rest) = t.split_at(1);
-                    Line::from(vec![
-                        Span::styled(
-                            first,
-                            Style::default()
-                                .fg(Color::Red)
-                                .add_modifier(Modifier::UNDERLINED),
-                        ),
-                        Span::styled(rest, Style::default().fg(Color::LightRed)),
-                    ])
-                }
+                // Underline the key opening the tab; it's the first letter unless that one is taken
+                let key = match t.as_str() {
+                    "Messages" => 2,
+                    "System" => 1,
+                    _ => 0,
+                };
+                let (first, rest) = t.split_at(key);
+                let (hotkey, rest) = rest.split_at(1);
+                Line::from(vec![
+                    Span::styled(first, Style::default().fg(Color::LightRed)),
+                    Span::styled(
+                        hotkey,
+                        Style::default()
+                            .fg(Color::Red)
+                            .add_modifier(Modifier::UNDERLINED),
+                    ),
+                    Span::styled(rest, Style::default().fg(Color::LightRed)),
+                ])
             })
             .collect();
 
+        // Tabs puts a space before and after each title and a divider between them
+        let inner = Rect { x: chunks[0].x + 1, y: chunks[0].y + 1, width: chunks[0].width.saturating_sub(2), height: 1 };
+        let mut x = inner.x;
+        self.tab_areas = self.menu_titles.iter().map(|title| {
+            let area = Rect { x, y: inner.y, width: title.chars().count() as u16 + 2, height: 1 }.intersection(inner);
+            x += area.width + 1;
+            area
+        }).collect();
+
         let tabs = Tabs::new(menu)
             .select(self.active_menu_item)
             .block(Block::default()
@@ -119,21 +171,46 @@ impl Ui {
         if let Some(l) = &self.sel_list {
             list = l
         }
-        let header = Paragraph::new(format!("Selected domain: {} || Selected list: {}", domain, list))
+        let mut header = format!("Selected domain: {} || Selected list: {}", domain, list);
+        if self.searching {
+            header = format!("{} || Search: /{}_", header, self.filter);
+        } else if !self.filter.is_empty() {
+            header = format!("{} || Filter: /{} ({} of {})", header, self.filter, self.view.len(), self.rows.len());
+        }
+        if !self.marked.is_empty() {
+            header = format!("{} || Marked: {}", header, self.marked.len());
+        }
+        let header = Paragraph::new(header)
             .style(Style::default().fg(Color::LightRed));
 
         frame.render_widget(header, chunks[1]);
 
         let style = Style::default().fg(Color::Blue);
-        let lv: Vec<Line<'_>> = self.list_vec.iter().map(|s| {
-            Line::styled(s.clone(), style)
+        let rows: Vec<Row<'_>> = self.view.iter().map(|i| {
+            if self.marked.contains(i) {
+                Row::new(self.rows[*i].clone()).style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
+            } else {
+                Row::new(self.rows[*i].clone()).style(style)
+            }
         }).collect();
-        let list = List::new(lv)
-            .highlight_style(Style::default().fg(Color::Red).add_modifier(Modifier::BOLD));
+        let mut table = Table::new(rows, self.widths())
+            .row_highlight_style(Style::default().fg(Color::Red).add_modifier(Modifier::BOLD));
+        if let Some(header) = &self.header {
+            let cells: Vec<String> = header.iter().enumerate().map(|(i, title)| {
+                match self.sort {
+                    Some((column, false)) if column == i => format!("{} ▲", title),
+                    Some((column, true)) if column == i => format!("{} ▼", title),
+                    _ => title.clone(),
+                }
+            }).collect();
+            table = table.header(Row::new(cells)
+                .style(Style::default().fg(Color::LightRed).add_modifier(Modifier::BOLD)));
+        }
 
-        frame.render_stateful_widget(list, chunks[2], &mut self.state);
+        frame.render_stateful_widget(table, chunks[2], &mut self.state);
+        self.table_area = chunks[2];
 
-        let status = Paragraph::new(format!("{}", self.status))
+        let status = Paragraph::new(self.status.clone())
             .block(Block::default()
                 .title(" Status ")
                 .borders(Borders::ALL)
@@ -145,7 +222,16 @@ impl Ui {
         frame.render_widget(status, chunks[3]);
     }
 
+    pub fn active_menu_item(&self) -> MenuItem {
+        self.active_menu_item
+    }
+
     pub fn set_active_menu_item(&mut self, menu_item: MenuItem) {
+        if self.active_menu_item != menu_item {
+            self.filter.clear();
+            self.searching = false;
+            self.sort = None;
+        }
         self.active_menu_item = menu_item;
     }
 
@@ -158,16 +244,188 @@ impl Ui {
     }
 
     pub fn set_list_vec(&mut self, list_vec: Vec<String>) {
-        self.list_vec = list_vec;
-        self.state.select(Some(0));
+        self.header = None;
+        self.rows = list_vec.into_iter().map(|s| vec![s]).collect();
+        self.sort = None;
+        self.marked.clear();
+        self.apply_filter();
+    }
+
+    pub fn set_table(&mut self, header: Vec<String>, rows: Vec<Vec<String>>) {
+        if let Some((column, _)) = self.sort {
+            if column >= header.len() {
+                self.sort = None;
+            }
+        }
+        self.header = Some(header);
+        self.rows = rows;
+        self.marked.clear();
+        self.apply_filter();
+    }
+
+    // Like set_table, but keeps the selected and marked rows, e.g. for periodically reloaded views
+    pub fn refresh_table(&mut self, header: Vec<String>, rows: Vec<Vec<String>>) {
+        let selected = self.state.selected();
+        let marked = std::mem::take(&mut self.marked);
+        self.set_table(header, rows);
+        self.marked = marked.into_iter().filter(|i| *i < self.rows.len()).collect();
+        if let Some(i) = selected {
+            if i < self.view.len() {
+                self.state.select(Some(i));
+            }
+        }
+    }
+
+    // Sorting by the same column again reverses the order
+    pub fn sort_by(&mut self, column: usize) -> bool {
+        match &self.header {
+            Some(header) if column < header.len() => {
+                self.sort = match self.sort {
+                    Some((c, descending)) if c == column => Some((column, !descending)),
+                    _ => Some((column, false)),
+                };
+                self.apply_filter();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn tab_at(&self, column: u16, row: u16) -> Option<usize> {
+        self.tab_areas.iter().position(|area| area.contains(Position { x: column, y: row }))
+    }
+
+    // The position of the clicked row among the shown ones, as taken by select()
+    pub fn row_at(&self, column: u16, row: u16) -> Option<usize> {
+        if !self.table_area.contains(Position { x: column, y: row }) {
+            return None;
+        }
+        let header = if self.header.is_some() { 1 } else { 0 };
+        let i = (row - self.table_area.y).checked_sub(header)? as usize + self.state.offset();
+        if i < self.view.len() {
+            Some(i)
+        } else {
+            None
+        }
+    }
+
+    // Position among the shown rows, unlike selected()
+    pub fn selected_row(&self) -> Option<usize> {
+        self.state.selected()
     }
 
     pub fn select(&mut self, i: Option<usize>) {
         self.state.select(i)
     }
 
+    // Index into the unfiltered and unsorted rows, so callers can use it for their entries directly
     pub fn selected(&self) -> Option<usize> {
-        self.state.selected()
+        self.state.selected().and_then(|i| self.view.get(i).copied())
+    }
+
+    // Marks or unmarks the selected row and moves on, so several rows can be marked in a row
+    pub fn toggle_mark(&mut self) -> bool {
+        if self.header.is_none() {
+            return false;
+        }
+        if let Some(i) = self.selected() {
+            if !self.marked.remove(&i) {
+                self.marked.insert(i);
+            }
+            self.down();
+        }
+        true
+    }
+
+    // Indices into the unfiltered and unsorted rows, like selected()
+    pub fn marked(&self) -> Vec<usize> {
+        self.marked.iter().copied().collect()
+    }
+
+    pub fn searching(&self) -> bool {
+        self.searching
+    }
+
+    pub fn start_search(&mut self) {
+        self.searching = true;
+        self.filter.clear();
+        self.apply_filter();
+    }
+
+    pub fn search_input(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Esc => {
+                self.searching = false;
+                self.filter.clear();
+                self.apply_filter();
+            }
+            KeyCode::Enter => self.searching = false,
+            KeyCode::Backspace => {
+                self.filter.pop();
+                self.apply_filter();
+            }
+            KeyCode::Char(c) => {
+                self.filter.push(c);
+                self.apply_filter();
+            }
+            _ => {}
+        }
+    }
+
+    // Only the first line, a search can't span several
+    pub fn search_paste(&mut self, text: &str) {
+        self.filter.push_str(text.lines().map(str::trim).find(|line| !line.is_empty()).unwrap_or_default());
+        self.apply_filter();
+    }
+
+    pub fn filter_active(&self) -> bool {
+        !self.filter.is_empty()
+    }
+
+    fn widths(&self) -> Vec<Constraint> {
+        let columns = self.rows.iter().map(Vec::len).max().unwrap_or(1);
+        (0..columns).map(|column| {
+            let mut width = self.header
+                .as_ref()
+                .and_then(|header| header.get(column))
+                .map(|title| title.chars().count() + 2)
+                .unwrap_or(0);
+            for row in &self.rows {
+                if let Some(cell) = row.get(column) {
+                    width = width.max(cell.chars().count());
+                }
+            }
+            if column == 0 {
+                Constraint::Min(width.min(40) as u16)
+            } else {
+                Constraint::Length(width.min(40) as u16)
+            }
+        }).collect()
+    }
+
+    fn apply_filter(&mut self) {
+        self.view = self.rows
+            .iter()
+            .enumerate()
+            .filter(|(_, row)| fuzzy_match(&self.filter, &row.join(" ")))
+            .map(|(i, _)| i)
+            .collect();
+        if let Some((column, descending)) = self.sort {
+            let rows = &self.rows;
+            self.view.sort_by(|a, b| {
+                let ordering = compare_cells(&rows[*a][column], &rows[*b][column]);
+                if descending {
+                    ordering.reverse()
+                } else {
+                    ordering
+                }
+            });
+        }
+        if self.view.is_empty() {
+            self.state.select(None);
+        } else {
+            self.state.select(Some(0));
+        }
     }
 
     pub fn set_status(&mut self, status: String) {
@@ -175,9 +433,12 @@ impl Ui {
     }
 
     pub fn down(&mut self) {
+        if self.view.is_empty() {
+            return;
+        }
         let i = match self.state.selected() {
             Some(i) => {
-                if i >= self.list_vec.len() - 1 {
+                if i >= self.view.len() - 1 {
                     0
                 } else {
                     i + 1
@@ -189,10 +450,13 @@ impl Ui {
     }
 
     pub fn up(&mut self) {
+        if self.view.is_empty() {
+            return;
+        }
         let i = match self.state.selected() {
             Some(i) => {
                 if i == 0 {
-                    self.list_vec.len() - 1
+                    self.view.len() - 1
                 } else {
                     i - 1
                 }
@@ -201,4 +465,21 @@ impl Ui {
         };
         self.state.select(Some(i));
     }
-}
\ No newline at end of file
+}
+
+// Case insensitive subsequence match: "jdo" matches "john.doe@example.org"
+fn fuzzy_match(pattern: &str, text: &str) -> bool {
+    let mut chars = text.chars().flat_map(char::to_lowercase);
+    pattern
+        .chars()
+        .flat_map(char::to_lowercase)
+        .all(|p| chars.any(|c| c == p))
+}
+
+// Numbers like member_count or bounce_score are compared by value, everything else as text
+fn compare_cells(a: &str, b: &str) -> Ordering {
+    match (a.parse::<f64>(), b.parse::<f64>()) {
+        (Ok(a), Ok(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+        _ => a.to_lowercase().cmp(&b.to_lowercase()),
+    }
+}
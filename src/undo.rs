@@ -0,0 +1,82 @@
+use std::collections::HashMap;
+
+use reqwest::{Method, Url};
+use serde_json::{Map, value::Value};
+
+use crate::{config::Config, members, popup::PopupReqParam};
+
+// Keeps memory bounded in long sessions; older actions fall off the bottom
+const DEPTH: usize = 20;
+
+// What it takes to take back a destructive action, captured before the action is sent
+#[derive(Clone)]
+pub enum Undo {
+    // Requests recreating what the action removed or changed, sent in order
+    Restore(String, Vec<PopupReqParam>),
+    // The server keeps nothing to recreate it from, e.g. a discarded message
+    Impossible(String),
+}
+
+#[derive(Default)]
+pub struct UndoStack {
+    entries: Vec<Undo>,
+}
+
+impl Undo {
+    pub fn description(&self) -> String {
+        match self {
+            Undo::Restore(description, _) |
+            Undo::Impossible(description) => description.clone(),
+        }
+    }
+}
+
+impl UndoStack {
+    pub fn push(&mut self, undo: Undo) {
+        if self.entries.len() == DEPTH {
+            self.entries.remove(0);
+        }
+        self.entries.push(undo);
+    }
+
+    pub fn pop(&mut self) -> Option<Undo> {
+        self.entries.pop()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+// Subscribes a deleted member again with the role, name and delivery mode they had,
+// then puts back their preferences if they were read before the deletion
+pub fn resubscribe(config: &Config, member: &members::Entry, preferences: Option<&Map<String, Value>>) -> Vec<PopupReqParam> {
+    let url = |path: &str| Url::parse(&format!("{}://{}:{}/3.1/{}",
+        config.protocol(),
+        config.host(),
+        config.port(),
+        path)).unwrap();
+    let mut params = Vec::new();
+
+    let mut map = HashMap::new();
+    map.insert("list_id".to_string(), member.list_id());
+    map.insert("subscriber".to_string(), member.email());
+    map.insert("role".to_string(), member.role());
+    map.insert("display_name".to_string(), member.display_name());
+    map.insert("delivery_mode".to_string(), member.delivery_mode());
+    map.insert("pre_verified".to_string(), "true".to_string());
+    map.insert("pre_confirmed".to_string(), "true".to_string());
+    map.insert("pre_approved".to_string(), "true".to_string());
+    map.insert("send_welcome_message".to_string(), "false".to_string());
+    params.push(PopupReqParam::new(Method::POST, url("members"), map).with_label(format!("subscribing {}", member.email())));
+
+    // The new membership gets a new id, so it's addressed by role and email
+    if let Some(preferences) = preferences.filter(|preferences| !preferences.is_empty()) {
+        let path = format!("lists/{}/{}/{}/preferences", member.list_id(), member.role(), member.email());
+        params.push(PopupReqParam::new_json(Method::PATCH, url(&path), preferences.clone())
+            .with_label(format!("preferences of {}", member.email()))
+            .if_previous_succeeded());
+    }
+
+    params
+}